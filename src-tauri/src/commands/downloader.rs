@@ -1,21 +1,34 @@
+use crate::models::config::DownloadSettings;
+use crate::models::download_job::DownloadProgress;
 use crate::models::downloader::{Manifest, VersionInfo, VersionLoader};
 use crate::models::error::{AppError, Void};
+use crate::models::garbage::GarbageReport;
+use crate::models::install_plan::InstallPlan;
 use crate::models::mirror::Mirror;
-use crate::models::versions::VersionBase::{FABRIC, FORGE};
+use crate::models::versions::VersionBase::{FABRIC, FORGE, LITELOADER, NEOFORGE, QUILT};
 use crate::models::versions::{MinecraftVersion, VersionBase, VersionCategory, VersionType};
 use crate::services::game_downloader::{
-    download_fabric, download_forge_version, get_available_fabric_versions,
-    get_available_forge_versions,
+    get_available_fabric_versions, get_available_forge_versions, get_available_neoforge_versions,
+    get_available_quilt_versions,
 };
-use crate::services::utils::update_download_status;
 use crate::services::version_manager::{
-    download_version_manifest, load_version_manifest, load_version_manifest_local,
+    load_version_manifest, load_version_manifest_local, refresh_version_manifest,
 };
-use crate::services::{game_downloader, version_manager};
+use crate::services::game_downloader::download_file_from_mirrors;
+use crate::services::http_client::HttpClient;
+use crate::services::liteloader::get_available_liteloader_versions;
+use crate::services::optifine_installer::install_optifine;
+use crate::services::utils::remove_if_exists;
+use crate::services::{garbage_collector, install_planner, version_manager};
 use crate::{AppState, GLOBAL_CACHE};
-use log::info;
+use std::future::Future;
+use std::path::PathBuf;
 use tauri::{command, AppHandle, State};
+use log::info;
+use reqwest::Url;
 use tauri_plugin_dialog::DialogExt;
+use tokio::sync::oneshot;
+use uuid::Uuid;
 
 #[command]
 pub async fn get_vanilla_versions(
@@ -23,10 +36,10 @@ pub async fn get_vanilla_versions(
     state: State<'_, AppState>,
 ) -> Result<Vec<VersionCategory>, AppError> {
     let cfg = state.config.read().await;
-    let mirror = &cfg.download_settings.mirror;
+    let settings = &cfg.download_settings;
     let mut result: Vec<VersionCategory> = Vec::new();
 
-    let manifest = load_version_manifest(mirror).await?;
+    let manifest = load_version_manifest(&state.http, settings).await?;
     let versions: Vec<&VersionInfo> = manifest
         .versions
         .iter()
@@ -103,46 +116,28 @@ pub async fn get_forge_versions(
     state: State<'_, AppState>,
 ) -> Result<Vec<VersionCategory>, AppError> {
     let cfg = state.config.read().await;
-    let mirror = &cfg.download_settings.mirror;
-
-    let manifest = load_version_manifest(mirror).await?;
-    let mut result: Vec<VersionCategory> = Vec::new();
-    let versions: Vec<&VersionInfo> = manifest
-        .versions
-        .iter()
-        .filter(|x| matches!(x.version_type, VersionType::Release))
-        .collect();
-
-    for ver in versions {
-        let id = ver.id.clone();
-        let id_args: Vec<&str> = id.split('.').collect();
-        let category = format!("{}.{}", id_args[0], id_args[1]);
-        let pos = result.iter().position(|x| x.name == category);
-        let cat = match pos {
-            Some(idx) => &mut result[idx],
-            None => {
-                let c = VersionCategory {
-                    versions: vec![],
-                    name: category.clone(),
-                };
-                result.push(c);
-                result.last_mut().ok_or(AppError::UnknownError(
-                    "Couldn't get the last item of a vector which is kinda guaranteed to exist!"
-                        .to_string(),
-                ))?
-            }
-        };
-        let mut forge_versions = get_available_forge_versions(&id, mirror).await?;
+    let settings = &cfg.download_settings;
+    let http = &state.http;
+    get_loader_versions(http, settings, FORGE, |id| async move {
+        let mut forge_versions = get_available_forge_versions(http, &id, settings).await?;
         forge_versions.reverse();
-        cat.versions
-            .extend(forge_versions.into_iter().map(|x| VersionLoader {
-                id: x,
-                base: FORGE,
-                date: "FORGE".to_string(),
-            }));
-    }
-
-    Ok(result)
+        Ok(forge_versions)
+    })
+    .await
+}
+#[command]
+pub async fn get_neoforge_versions(
+    state: State<'_, AppState>,
+) -> Result<Vec<VersionCategory>, AppError> {
+    let cfg = state.config.read().await;
+    let settings = &cfg.download_settings;
+    let http = &state.http;
+    get_loader_versions(http, settings, NEOFORGE, |id| async move {
+        let mut neoforge_versions = get_available_neoforge_versions(http, &id, settings).await?;
+        neoforge_versions.reverse();
+        Ok(neoforge_versions)
+    })
+    .await
 }
 #[command]
 pub async fn get_fabric_versions(
@@ -150,9 +145,49 @@ pub async fn get_fabric_versions(
     state: State<'_, AppState>,
 ) -> Result<Vec<VersionCategory>, AppError> {
     let cfg = state.config.read().await;
-    let mirror = &cfg.download_settings.mirror;
-    let manifest = load_version_manifest(mirror).await?;
+    let settings = &cfg.download_settings;
+    let http = &state.http;
+    get_loader_versions(http, settings, FABRIC, |id| async move {
+        get_available_fabric_versions(http, &id, settings).await
+    })
+    .await
+}
+#[command]
+pub async fn get_quilt_versions(state: State<'_, AppState>) -> Result<Vec<VersionCategory>, AppError> {
+    let cfg = state.config.read().await;
+    let settings = &cfg.download_settings;
+    let http = &state.http;
+    get_loader_versions(http, settings, QUILT, |id| async move {
+        get_available_quilt_versions(http, &id, settings).await
+    })
+    .await
+}
+/// Lists LiteLoader's builds, which only exist for legacy versions (up to 1.12.2).
+#[command]
+pub async fn get_liteloader_versions(state: State<'_, AppState>) -> Result<Vec<VersionCategory>, AppError> {
+    let cfg = state.config.read().await;
+    let settings = &cfg.download_settings;
+    let http = &state.http;
+    let mut result = get_loader_versions(http, settings, LITELOADER, |id| async move {
+        get_available_liteloader_versions(http, &id, settings).await
+    })
+    .await?;
+    result.retain(|x| !x.versions.is_empty());
+    Ok(result)
+}
 
+/// Lists the builds `fetch` finds for every release, grouped by the `major.minor` category of the release.
+async fn get_loader_versions<F, Fut>(
+    client: &HttpClient,
+    settings: &DownloadSettings,
+    base: VersionBase,
+    fetch: F,
+) -> Result<Vec<VersionCategory>, AppError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Vec<String>, AppError>>,
+{
+    let manifest = load_version_manifest(client, settings).await?;
     let mut result: Vec<VersionCategory> = Vec::new();
     let versions: Vec<&VersionInfo> = manifest
         .versions
@@ -164,7 +199,6 @@ pub async fn get_fabric_versions(
         let id = ver.id.clone();
         let id_args: Vec<&str> = id.split('.').collect();
         let category = format!("{}.{}", id_args[0], id_args[1]);
-
         let pos = result.iter().position(|x| x.name == category);
         let cat = match pos {
             Some(idx) => &mut result[idx],
@@ -180,97 +214,138 @@ pub async fn get_fabric_versions(
                 ))?
             }
         };
-        let fabric_versions = get_available_fabric_versions(&id).await?;
-
+        let loader_versions = fetch(id).await?;
         cat.versions
-            .extend(fabric_versions.into_iter().map(|x| VersionLoader {
+            .extend(loader_versions.into_iter().map(|x| VersionLoader {
                 id: x,
-                base: FABRIC,
-                date: "FABRIC".to_string(),
+                base: base.clone(),
+                date: format!("{base:?}"),
             }));
     }
 
     Ok(result)
 }
+/// Applies an OptiFine installer onto an installed version. the installer is either a local path, a url to download
+/// it from, or picked with a dialog when missing. returns the version to launch, or `None` if nothing was picked.
+#[command]
+pub async fn add_optifine(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    installer: Option<String>,
+    version_id: String,
+) -> Result<Option<String>, AppError> {
+    let target = MinecraftVersion::from_id(version_id);
+    // The downloaded installer and whatever the patcher leaves behind go in a directory of their own.
+    let temp_dir = state.http.temp_directory().join(format!("optifine-{}", Uuid::new_v4()));
+    let result = async {
+        let installer = match installer {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {
+                let path = temp_dir.join(installer_file_name(&url));
+                let mirrors = state.config.read().await.download_settings.mirrors.clone();
+                download_file_from_mirrors(&state.http, &url, &path, "", 0, &mirrors).await?;
+                path
+            }
+            Some(path) => PathBuf::from(path),
+            None => match pick_optifine_installer(&app_handle).await? {
+                Some(path) => path,
+                None => return Ok(None),
+            },
+        };
+        install_optifine(&installer, &target, &temp_dir).await.map(Some)
+    }
+    .await;
+    if let Err(e) = remove_if_exists(&temp_dir) {
+        info!("Couldn't clean up after the OptiFine installer: {e}");
+    }
+    result
+}
+
+/// The name to save a downloaded installer as, the url's last segment when it's a plain jar name.
+fn installer_file_name(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|x| x.path_segments()?.next_back().map(|x| x.to_string()))
+        .filter(|x| x.ends_with(".jar") && x.chars().all(|c| c.is_ascii_alphanumeric() || "._-+".contains(c)))
+        .unwrap_or_else(|| "optifine-installer.jar".to_string())
+}
+
+/// Lets the user pick an installer without holding up a runtime worker while the dialog is open.
+async fn pick_optifine_installer(app_handle: &AppHandle) -> Result<Option<PathBuf>, AppError> {
+    let (tx, rx) = oneshot::channel();
+    app_handle
+        .dialog()
+        .file()
+        .add_filter("OptiFine installer".to_string(), &["jar"])
+        .pick_file(move |picked| {
+            let _ = tx.send(picked);
+        });
+    let picked = rx.await.map_err(|e| AppError::Internal(e.to_string()))?;
+    Ok(picked.and_then(|x| x.into_path().ok()))
+}
+/// Resolves everything installing the given version would download, without installing it.
+#[command]
+pub async fn plan_install(
+    state: State<'_, AppState>,
+    version_loader: VersionLoader,
+) -> Result<InstallPlan, AppError> {
+    let settings = state.config.read().await.download_settings.clone();
+    install_planner::plan_install(&state.http, &version_loader, &settings).await
+}
+
+/// Queues the installation of the given version and returns the id of its download job.
 #[command]
 pub async fn download_version(
     app_handle: AppHandle,
     state: State<'_, AppState>,
     version_loader: VersionLoader,
     name: String,
-) -> Result<(), AppError> {
-    let mut version_id = version_loader.get_installed_id();
-    let cfg = &state.config.read().await;
-    let mir = &cfg.download_settings.mirror;
-    let logger = &state.log_tx;
-    info!(
-        "DEBUG: Downloading version {} from {} mirror",
-        version_loader.id, mir.name
-    );
-    if version_loader.base == FORGE {
-        info!(
-            "DEBUG: Forge version detected! {} installing it rn!",
-            version_loader.id
-        );
-        let t = download_forge_version(
-            &version_loader.id,
-            &app_handle,
-            logger,
-            &mir,
-            &mut version_id,
-        )
-        .await;
-        if let Err(e) = &t {
-            info!("{:?}", e);
-        }
-    };
-    if version_loader.base == FABRIC {
-        info!(
-            "DEBUG: Fabric version detected! {} installing it rn!",
-            version_loader.id
-        );
-        download_fabric(&version_loader, logger, &mir).await?;
-    }
+) -> Result<String, AppError> {
+    let bandwidth_limit = state.config.read().await.download_settings.job_bandwidth_limit;
+    state
+        .download_manager
+        .enqueue(app_handle.clone(), version_loader, name, bandwidth_limit)
+}
 
-    info!("Downloading {version_id}.json");
+#[command]
+pub async fn list_downloads(state: State<'_, AppState>) -> Result<Vec<DownloadProgress>, AppError> {
+    state.download_manager.list()
+}
 
-    let manifest = load_version_manifest(mir).await?;
-    if version_loader.base == VersionBase::VANILLA {
-        game_downloader::download_from_manifest(&version_id, &manifest, mir).await?;
-    }
-    let version = MinecraftVersion::from_id(version_id);
+#[command]
+pub async fn pause_download(state: State<'_, AppState>, task_id: String) -> Void {
+    state.download_manager.pause(&task_id)
+}
 
-    let inherited_version = version.get_inherited();
-    info!("Detected inherited version is {}", inherited_version.id);
-    update_download_status("Downloading version...", &app_handle);
-    let cfg = &state.config.read().await;
-    let downloadable_version = if version_loader.base == VersionBase::VANILLA {
-        &version
-    } else {
-        &inherited_version
-    };
+#[command]
+pub async fn resume_download(state: State<'_, AppState>, task_id: String) -> Void {
+    state.download_manager.resume(&task_id)
+}
 
-    game_downloader::download_version(
-        &downloadable_version,
-        &name,
-        &app_handle,
-        logger,
-        &*cfg,
-    )
-    .await?;
-    if inherited_version.id != version.id {
-        game_downloader::download_version(&version, &name, &app_handle, logger, &*cfg)
-            .await?;
+#[command]
+pub async fn cancel_download(state: State<'_, AppState>, task_id: String) -> Void {
+    state.download_manager.cancel(&task_id)
+}
+
+/// Caps the bandwidth of a single job in bytes per second while it's queued or running, 0 lifts the cap.
+#[command]
+pub async fn set_download_bandwidth_limit(state: State<'_, AppState>, task_id: String, limit: u64) -> Void {
+    state.download_manager.set_bandwidth_limit(&task_id, limit)
+}
+
+/// Removes the completed, failed and cancelled jobs from the downloads list.
+#[command]
+pub async fn clear_finished_downloads(state: State<'_, AppState>) -> Void {
+    state.download_manager.clear_finished()
+}
+
+/// Looks for the libraries, assets, Java runtimes and stored objects no installed version uses anymore, and deletes
+/// them when `confirm` is set. Refused while downloads are running since their files aren't referenced yet.
+#[command]
+pub async fn collect_garbage(state: State<'_, AppState>, confirm: bool) -> Result<GarbageReport, AppError> {
+    if state.download_manager.has_unfinished()? {
+        return Err(AppError::DownloadsInProgress);
     }
-    update_download_status("", &app_handle);
-    app_handle
-        .dialog()
-        .message("Successfully installed the selected version you can now play it")
-        .title("Done!")
-        .blocking_show();
-    let mut global = GLOBAL_CACHE.lock().await;
-    global.versions.push(version);
-    Ok(())
+    garbage_collector::collect_garbage(confirm).await
 }
 
 /// Gives the available versions to download
@@ -296,9 +371,9 @@ pub async fn get_installed_versions() -> Result<Vec<String>, AppError> {
         .collect())
 }
 #[command]
-/// Downloads the latest version manifest available through the given mirror whether it already exists or not.
+/// Revalidates the cached version manifest through the configured mirrors whether it's still fresh or not.
 pub async fn reload_version_manifest(app_handle: AppHandle, state: State<'_, AppState>) -> Void {
     let cfg = state.config.read().await;
-    let mirror = &cfg.download_settings.mirror;
-    download_version_manifest(mirror).await
+    refresh_version_manifest(&state.http, &cfg.download_settings).await?;
+    Ok(())
 }
@@ -6,8 +6,16 @@ use crate::models::profiles::get_profile;
 use crate::services::directory_manager::{
     get_assets_directory, get_minecraft_directory, get_natives_directory,
 };
+use crate::models::install_plan::PlannedFile;
+use crate::models::launch_context::QuickPlay;
+use crate::models::verification::VerificationReport;
+use crate::models::versions::MinecraftVersion;
+use crate::services::asset_layout::materialize_assets;
 use crate::services::game_downloader::download_version;
-use crate::services::game_launcher::{get_jvm_args, get_launch_args};
+use crate::services::install_planner::{plan_offline_launch, unreadable_version_json};
+use crate::services::verifier;
+use crate::services::game_launcher::{build_launch_context, get_jvm_args, get_launch_args};
+use crate::services::mirror_manager::apply_auto_mirror;
 use crate::services::utils;
 use crate::services::utils::{extend_once, patch_java_permission_linux, vec_to_string};
 use crate::{services, AppState, GLOBAL_CACHE};
@@ -20,6 +28,43 @@ use std::sync::Mutex;
 use tauri::{command, AppHandle, Manager, State};
 use uuid::Uuid;
 use log::info;
+/// The files that are keeping the selected version from being launched in offline mode.
+#[command]
+pub async fn get_offline_launch_blockers(selected_version: String) -> Result<Vec<PlannedFile>, AppError> {
+    let global_cache = GLOBAL_CACHE.lock().await;
+    let version = global_cache
+        .versions
+        .iter()
+        .find(|x| x.id == selected_version)
+        .ok_or(AppError::VersionNotFound)?;
+    find_offline_launch_blockers(version).await
+}
+
+/// Checks the selected version's files against their manifests without repairing anything, unlike `repair_mode`.
+#[command]
+pub async fn verify_version(selected_version: String) -> Result<VerificationReport, AppError> {
+    // Hashing every file takes a while, the cache isn't kept locked meanwhile.
+    let version = GLOBAL_CACHE
+        .lock()
+        .await
+        .versions
+        .iter()
+        .find(|x| x.id == selected_version)
+        .cloned()
+        .ok_or(AppError::VersionNotFound)?;
+    verifier::verify_version(&version).await
+}
+
+async fn find_offline_launch_blockers(version: &MinecraftVersion) -> Result<Vec<PlannedFile>, AppError> {
+    let mut plan = match plan_offline_launch(version).await {
+        Ok(plan) => plan,
+        // The files of a version can't be told without its JSON, so that's what's blocking it.
+        Err(e) => return Ok(vec![unreadable_version_json(version).ok_or(e)?]),
+    };
+    plan.check_installed();
+    Ok(plan.files.into_iter().filter(|x| !x.installed).collect())
+}
+
 #[command]
 pub async fn play(
     app_handle: AppHandle,
@@ -27,6 +72,7 @@ pub async fn play(
     selected_version: String,
     repair_mode: bool,
     profile: &str,
+    quick_play: Option<QuickPlay>,
 ) -> Void {
     log::info!("Launching minecraft {selected_version} ");
     let global_cache = &*GLOBAL_CACHE.lock().await;
@@ -77,22 +123,37 @@ pub async fn play(
             )
         })?;
 
-    if repair_mode {
+    if config.launcher_settings.offline_mode.boolean() {
+        if repair_mode {
+            info!("Offline mode is enabled. skipping the repair since it requires network access");
+        }
+        info!("Offline mode is enabled. verifying {version_id}'s local files");
+        let missing = find_offline_launch_blockers(version).await?;
+        if !missing.is_empty() {
+            return Err(AppError::OfflineLaunchBlocked(
+                missing.iter().map(|x| x.path.display().to_string()).collect(),
+            ));
+        }
+    } else if repair_mode {
         info!("Repair mode is enabled. attempting to download/check version files");
+        let mut download_settings = config.download_settings.clone();
+        apply_auto_mirror(&state.http, &mut download_settings).await?;
         download_version(
+        &state.http,
         &inherited_version,
         &"".to_string(),
         &app_handle,
         &state.log_tx,
-        &config,
+        &download_settings,
     )
         .await?;
         download_version(
+            &state.http,
             &version,
             &"".to_string(),
             &app_handle,
             &state.log_tx,
-            &config,
+            &download_settings,
         )
         .await?;
 
@@ -106,12 +167,6 @@ pub async fn play(
     info!("Game Directory is {}", game_directory);
     let asset_directory = get_assets_directory().display().to_string();
     info!("Asset Directory is {}", asset_directory);
-    let resources_directory = get_minecraft_directory()
-        .join("resources")
-        .display()
-        .to_string();
-    info!("Resources Directory is {}", resources_directory.to_string());
-
     info!("Fetching libraries.");
     let libraries = version.get_libraries();
 
@@ -127,6 +182,14 @@ pub async fn play(
         })?
         .to_string();
 
+    // Legacy indexes read their objects by name. layouts that are complete are skipped outside of the repair mode,
+    // the others (e.g. of versions installed before the launcher knew about it) get finished here.
+    let game_assets = materialize_assets(&asset_index, repair_mode)?
+        .unwrap_or_else(|| get_minecraft_directory().join("resources"))
+        .display()
+        .to_string();
+    info!("Game Assets Directory is {game_assets}");
+
     let main_class = json
         .get("mainClass")
         .and_then(|v| v.as_str())
@@ -154,15 +217,24 @@ pub async fn play(
     })?;
     info!("Version type: {typ}");
 
-    let run_args_iter = get_launch_args(&json)?;
+    let context = build_launch_context(launch_options, quick_play.as_ref());
+    let run_args_iter = get_launch_args(&json, &context)?;
     info!("Fetching jvm args");
-    let mut jvm_args = get_jvm_args(&json);
-    let jvm_args_inherited = get_jvm_args(&inherited_json);
+    let mut jvm_args = get_jvm_args(&json, &context);
+    let jvm_args_inherited = get_jvm_args(&inherited_json, &context);
     jvm_args = extend_once(jvm_args_inherited, jvm_args);
     info!("Fetching runtime arguments.");
-    let run_args_iter_inherited = get_launch_args(&inherited_json)?;
+    let run_args_iter_inherited = get_launch_args(&inherited_json, &context)?;
     let run_args_iter_sum = extend_once(run_args_iter, run_args_iter_inherited);
 
+    let quick_play_target = quick_play.as_ref().map(|x| x.target()).unwrap_or_default();
+    let quick_play_path = get_minecraft_directory()
+        .join("quickPlay")
+        .join("java")
+        .join(format!("{}.json", Uuid::new_v4()))
+        .display()
+        .to_string();
+
     let mut run_args = run_args_iter_sum
         .iter()
         .map(|v| {
@@ -170,7 +242,7 @@ pub async fn play(
                 .replace("${version_name}", &version.id)
                 .replace("${game_directory}", &game_directory)
                 .replace("${assets_root}", &asset_directory)
-                .replace("${game_assets}", &resources_directory)
+                .replace("${game_assets}", &game_assets)
                 .replace("${assets_index_name}", &asset_index)
                 .replace("${auth_uuid}", &uid.to_string())
                 .replace("${auth_access_token}", "accessToken123")
@@ -179,6 +251,12 @@ pub async fn play(
                 .replace("${version_type}", typ)
                 .replace("${clientid}", &Uuid::new_v4().to_string())
                 .replace("${auth_xuid}", "0")
+                .replace("${resolution_width}", &launch_options.resolution_width.to_string())
+                .replace("${resolution_height}", &launch_options.resolution_height.to_string())
+                .replace("${quickPlayPath}", &quick_play_path)
+                .replace("${quickPlaySingleplayer}", quick_play_target)
+                .replace("${quickPlayMultiplayer}", quick_play_target)
+                .replace("${quickPlayRealms}", quick_play_target)
         })
         .collect::<Vec<String>>();
 
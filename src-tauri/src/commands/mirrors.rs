@@ -1,5 +1,7 @@
 use crate::models::error::AppError;
-use crate::models::mirror::{list_mirrors, Mirror};
+use crate::models::config::Bool;
+use crate::models::mirror::{list_mirrors, Mirror, MirrorBenchmark};
+use crate::services::mirror_manager;
 use crate::services::directory_manager::get_mirrors_dir;
 use crate::AppState;
 use std::fs;
@@ -10,15 +12,39 @@ pub async fn get_available_mirrors() -> Result<Vec<Mirror>, AppError> {
     list_mirrors()
 }
 
+/// Makes the given mirror the most preferred one, the rest of the list stays as its fallbacks.
 #[command]
 pub async fn set_mirror(app_handle: AppHandle, state: State<'_, AppState>, mirror: Mirror) -> Result<(), AppError> {
-    state.config.write().await.download_settings.mirror = mirror;
+    let mirrors = &mut state.config.write().await.download_settings.mirrors;
+    mirrors.retain(|x| x.name != mirror.name);
+    mirrors.insert(0, mirror);
     Ok(())
 }
 
 #[command]
 pub async fn get_mirror(app_handle: AppHandle, state: State<'_, AppState>) -> Result<Mirror, AppError> {
-    Ok(state.config.read().await.download_settings.mirror.clone())
+    Ok(state.config.read().await.download_settings.primary_mirror())
+}
+
+/// Replaces the whole mirror list, in the order they should be tried.
+#[command]
+pub async fn set_mirrors(state: State<'_, AppState>, mirrors: Vec<Mirror>) -> Result<(), AppError> {
+    let mut unique: Vec<Mirror> = Vec::new();
+    for mirror in mirrors {
+        if !unique.iter().any(|x| x.name == mirror.name) {
+            unique.push(mirror);
+        }
+    }
+    if unique.is_empty() {
+        unique.push(Mirror::default());
+    }
+    state.config.write().await.download_settings.mirrors = unique;
+    Ok(())
+}
+
+#[command]
+pub async fn get_mirrors(state: State<'_, AppState>) -> Result<Vec<Mirror>, AppError> {
+    Ok(state.config.read().await.download_settings.mirrors.clone())
 }
 
 #[command]
@@ -30,3 +56,26 @@ pub async fn import_mirror(json: String) -> Result<(), AppError> {
         Err(AppError::JsonParseFailed("Invalid json format".to_string()))
     }
 }
+
+/// Measures every available mirror's latency and throughput and stores the results for the auto mirror mode.
+#[command]
+pub async fn benchmark_mirrors(state: State<'_, AppState>) -> Result<Vec<MirrorBenchmark>, AppError> {
+    mirror_manager::benchmark_mirrors(&state.http).await
+}
+
+/// The results of the latest benchmark, empty if no benchmark was taken yet.
+#[command]
+pub async fn get_mirror_benchmarks() -> Result<Vec<MirrorBenchmark>, AppError> {
+    Ok(mirror_manager::load_benchmarks())
+}
+
+#[command]
+pub async fn set_auto_mirror(state: State<'_, AppState>, toggle: bool) -> Result<(), AppError> {
+    state.config.write().await.download_settings.auto_mirror = Bool::new(toggle);
+    Ok(())
+}
+
+#[command]
+pub async fn is_auto_mirror(state: State<'_, AppState>) -> Result<bool, AppError> {
+    Ok(state.config.read().await.download_settings.auto_mirror.boolean())
+}
@@ -6,4 +6,5 @@ pub mod settings;
 pub mod mods;
 pub mod game_launcher;
 pub mod process_manager;
-pub mod modrinth_helper;
\ No newline at end of file
+pub mod modrinth_helper;
+pub mod proxy;
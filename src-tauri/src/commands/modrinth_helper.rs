@@ -2,12 +2,17 @@ use crate::models::error::{AppError, Void};
 use crate::models::modrinth::{DependencyType, ModrinthMod, ModrinthSearchResult, ModrinthVersion};
 use crate::services::directory_manager::get_mods_directory;
 use crate::services::game_downloader::download_file_if_not_exists;
+use crate::models::mirror::Mirror;
+use crate::services::http_client::HttpClient;
+use crate::services::mirror_manager::get_json_from_mirrors;
+use crate::AppState;
 use log::info;
 use serde::{Deserialize, Serialize};
-use tauri::command;
+use tauri::{command, State};
 
 #[command]
 pub async fn search_for_modrinth_project(
+    state: State<'_, AppState>,
     name: String,
     facets: String,
     index: String,
@@ -16,64 +21,70 @@ pub async fn search_for_modrinth_project(
 ) -> Result<ModrinthSearchResult, AppError> {
     /// https://docs.modrinth.com/api/operations/searchprojects/ for more details
     let api = format!("https://api.modrinth.com/v2/search?query={name}&facets={facets}&offset={offset}&limit={limit}&index={index}");
-    reqwest::get(&api)
-        .await
-        .map_err(|e| AppError::NetworkRequestFailed(format!("Modrinth API request failed: {}", e)))?
-        .json::<ModrinthSearchResult>()
-        .await
-        .map_err(|e| {
-            AppError::JsonParseFailed(format!("Failed to parse Modrinth search results: {}", e))
-        })
+    let mirrors = state.config.read().await.download_settings.mirrors.clone();
+    get_json_from_mirrors::<ModrinthSearchResult>(&state.http, &api, &mirrors).await
 }
 #[command]
-pub async fn get_modrinth_projects(project_id: String) -> Result<ModrinthMod, AppError> {
+pub async fn get_modrinth_projects(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<ModrinthMod, AppError> {
     /// https://docs.modrinth.com/api/operations/getproject/ for more details.
     let api = format!("https://api.modrinth.com/v2/project/{project_id}");
-    reqwest::get(&api)
-        .await
-        .map_err(|e| AppError::NetworkRequestFailed(format!("Modrinth API request failed: {}", e)))?
-        .json::<ModrinthMod>()
-        .await
-        .map_err(|e| AppError::JsonParseFailed(format!("Failed to parse Modrinth results: {}", e)))
+    let mirrors = state.config.read().await.download_settings.mirrors.clone();
+    get_json_from_mirrors::<ModrinthMod>(&state.http, &api, &mirrors).await
 }
 #[command]
-pub async fn list_modrinth_mod_versions(project_id: String) -> Result<Vec<ModrinthVersion>, AppError> {
+pub async fn list_modrinth_mod_versions(
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<Vec<ModrinthVersion>, AppError> {
     /// https://docs.modrinth.com/api/operations/getprojectversions/ for more details.
     let api = format!("https://api.modrinth.com/v2/project/{project_id}/version");
-    reqwest::get(&api)
-        .await
-        .map_err(|e| AppError::NetworkRequestFailed(format!("Modrinth API request failed: {}", e)))?
-        .json::<Vec<ModrinthVersion>>()
-        .await
-        .map_err(|e| AppError::JsonParseFailed(format!("Failed to parse Modrinth results: {}", e)))
+    let mirrors = state.config.read().await.download_settings.mirrors.clone();
+    get_json_from_mirrors::<Vec<ModrinthVersion>>(&state.http, &api, &mirrors).await
 }
 #[command]
 pub async fn get_modrinth_mod_dependencies(
+    state: State<'_, AppState>,
     version: ModrinthVersion,
 ) -> Result<Vec<(ModrinthVersion, DependencyType)>, AppError> {
     let deps = version.dependencies;
+    let mirrors = state.config.read().await.download_settings.mirrors.clone();
     let mut result = Vec::new();
     for dep in deps.iter().filter(|x| x.version_id.is_some()) {
         let dep_type = dep.dependency_type.clone();
-        let version = get_modrinth_mod_version_by_id(dep.clone().version_id.unwrap()).await?;
+        let version = fetch_modrinth_version(&state.http, dep.clone().version_id.unwrap(), &mirrors).await?;
         result.push((version, dep_type))
     }
     Ok(result)
 }
 #[command]
-pub async fn get_modrinth_mod_version_by_id(version_id: String) -> Result<ModrinthVersion, AppError> {
+pub async fn get_modrinth_mod_version_by_id(
+    state: State<'_, AppState>,
+    version_id: String,
+) -> Result<ModrinthVersion, AppError> {
+    let mirrors = state.config.read().await.download_settings.mirrors.clone();
+    fetch_modrinth_version(&state.http, version_id, &mirrors).await
+}
+
+async fn fetch_modrinth_version(
+    client: &HttpClient,
+    version_id: String,
+    mirrors: &[Mirror],
+) -> Result<ModrinthVersion, AppError> {
     let api = format!("https://api.modrinth.com/v2/version/{version_id}");
-    reqwest::get(&api)
-        .await
-        .map_err(|e| AppError::NetworkRequestFailed(format!("Modrinth API request failed: {}", e)))?
-        .json::<ModrinthVersion>()
-        .await
-        .map_err(|e| AppError::JsonParseFailed(format!("Failed to parse Modrinth results: {}", e)))
+    get_json_from_mirrors::<ModrinthVersion>(client, &api, mirrors).await
 }
 /// name: minecraft version's name
 #[command]
-pub async fn download_modrinth_mod_version(version: ModrinthVersion, name: String) -> Void {
+pub async fn download_modrinth_mod_version(
+    state: State<'_, AppState>,
+    version: ModrinthVersion,
+    name: String,
+) -> Void {
     let files = version.files;
+    let mirrors = state.config.read().await.download_settings.mirrors.clone();
     let mods_dir = get_mods_directory();
     for file in files {
         let hashes = file.hashes;
@@ -82,7 +93,7 @@ pub async fn download_modrinth_mod_version(version: ModrinthVersion, name: Strin
         let url = file.url;
         let full_path = mods_dir.join(file.file_name);
         info!("Downloading from {url} to {}", full_path.to_string_lossy());
-        download_file_if_not_exists(&full_path, url, sha1.as_str(), size as u64).await?;
+        download_file_if_not_exists(&state.http, &full_path, url, sha1.as_str(), size as u64, &mirrors).await?;
     }
     Ok(())
 }
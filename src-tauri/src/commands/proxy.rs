@@ -0,0 +1,38 @@
+use crate::models::config::ProxySettings;
+use crate::models::error::AppError;
+use crate::services::http_client::{check_status, classify_error, HttpClient};
+use crate::AppState;
+use std::time::{Duration, Instant};
+use tauri::{command, State};
+
+const TEST_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[command]
+pub async fn get_proxy_settings(state: State<'_, AppState>) -> Result<ProxySettings, AppError> {
+    Ok(state.config.read().await.proxy_settings.clone())
+}
+
+/// Applies the proxy settings to every request from now on, downloads that are already running included.
+#[command]
+pub async fn set_proxy_settings(state: State<'_, AppState>, proxy: ProxySettings) -> Result<(), AppError> {
+    state.http.set_proxy(&proxy)?;
+    state.config.write().await.proxy_settings = proxy;
+    Ok(())
+}
+
+/// Sends a request through the given proxy settings without applying them, returns the round trip in milliseconds.
+#[command]
+pub async fn test_proxy(proxy: ProxySettings) -> Result<u64, AppError> {
+    let client = HttpClient::new(&proxy)?;
+    let start = Instant::now();
+    let resp = client
+        .without_retries()
+        .head(TEST_URL)
+        .timeout(TEST_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| classify_error(TEST_URL, &e))?;
+    check_status(TEST_URL, resp)?;
+    Ok(start.elapsed().as_millis() as u64)
+}
@@ -3,6 +3,7 @@ use crate::AppState;
 use crate::models::config::{Bool, Config};
 use crate::models::error::AppError;
 use crate::models::profiles::{get_profile, Profile};
+use crate::services::metadata_cache;
 
 #[command]
 pub async fn set_maximum_ram_usage(state: State<'_, AppState>, ram_usage: u64) -> Result<(), AppError> {
@@ -37,6 +38,31 @@ pub async fn should_use_dedicated_gpu(state: State<'_, AppState>) -> Result<bool
     Ok(state.config.read().await.launch_options.use_dedicated_gpu.boolean())
 }
 
+/// Sets the size of the game's window, 0 for either leaves it to the game.
+#[command]
+pub async fn set_resolution(state: State<'_, AppState>, width: u32, height: u32) -> Result<(), AppError> {
+    let mut config = state.config.write().await;
+    config.launch_options.resolution_width = width;
+    config.launch_options.resolution_height = height;
+    Ok(())
+}
+#[command]
+pub async fn get_resolution(state: State<'_, AppState>) -> Result<(u32, u32), AppError> {
+    let config = state.config.read().await;
+    Ok((config.launch_options.resolution_width, config.launch_options.resolution_height))
+}
+
+#[command]
+pub async fn set_demo_mode(state: State<'_, AppState>, toggle: bool) -> Result<(), AppError> {
+    let mut config = state.config.write().await;
+    config.launch_options.demo_mode = Bool::new(toggle);
+    Ok(())
+}
+#[command]
+pub async fn is_demo_mode(state: State<'_, AppState>) -> Result<bool, AppError> {
+    Ok(state.config.read().await.launch_options.demo_mode.boolean())
+}
+
 #[command]
 pub async fn get_language(state: State<'_, AppState>) -> Result<String, AppError> {
     let cfg = state.config.read().await;
@@ -62,6 +88,73 @@ pub async fn set_exit_on_launch(state: State<'_, AppState>, toggle: bool) -> Res
     Ok(())
 }
 
+#[command]
+pub async fn is_offline_mode(state: State<'_, AppState>) -> Result<bool, AppError> {
+    Ok(state.config.read().await.launcher_settings.offline_mode.boolean())
+}
+
+#[command]
+pub async fn set_offline_mode(state: State<'_, AppState>, toggle: bool) -> Result<(), AppError> {
+    let mut config = state.config.write().await;
+    config.launcher_settings.offline_mode = Bool::new(toggle);
+    Ok(())
+}
+
+#[command]
+pub async fn get_max_concurrent_downloads(state: State<'_, AppState>) -> Result<u64, AppError> {
+    Ok(state.config.read().await.download_settings.max_concurrent_downloads)
+}
+
+#[command]
+pub async fn set_max_concurrent_downloads(state: State<'_, AppState>, limit: u64) -> Result<(), AppError> {
+    let mut config = state.config.write().await;
+    config.download_settings.max_concurrent_downloads = limit.max(1);
+    Ok(())
+}
+
+#[command]
+pub async fn get_bandwidth_limit(state: State<'_, AppState>) -> Result<u64, AppError> {
+    Ok(state.config.read().await.download_settings.bandwidth_limit)
+}
+
+/// Caps the bandwidth all downloads share in bytes per second, running ones included. 0 lifts the cap.
+#[command]
+pub async fn set_bandwidth_limit(state: State<'_, AppState>, limit: u64) -> Result<(), AppError> {
+    let mut config = state.config.write().await;
+    config.download_settings.bandwidth_limit = limit;
+    state.http.bandwidth().set_limit(limit);
+    Ok(())
+}
+
+#[command]
+pub async fn get_job_bandwidth_limit(state: State<'_, AppState>) -> Result<u64, AppError> {
+    Ok(state.config.read().await.download_settings.job_bandwidth_limit)
+}
+
+/// The bandwidth cap new download jobs start out with, see `set_download_bandwidth_limit` for the running ones.
+#[command]
+pub async fn set_job_bandwidth_limit(state: State<'_, AppState>, limit: u64) -> Result<(), AppError> {
+    let mut config = state.config.write().await;
+    config.download_settings.job_bandwidth_limit = limit;
+    Ok(())
+}
+
+#[command]
+pub async fn get_metadata_cache_ttl(state: State<'_, AppState>) -> Result<u64, AppError> {
+    Ok(state.config.read().await.download_settings.metadata_cache_ttl)
+}
+
+#[command]
+pub async fn set_metadata_cache_ttl(state: State<'_, AppState>, seconds: u64) -> Result<(), AppError> {
+    let mut config = state.config.write().await;
+    config.download_settings.metadata_cache_ttl = seconds;
+    Ok(())
+}
+
+#[command]
+pub async fn clear_metadata_cache() -> Result<(), AppError> {
+    metadata_cache::clear_metadata_cache()
+}
 
 #[command]
 pub async fn save(state: State<'_, AppState>) -> Result<(), AppError> {
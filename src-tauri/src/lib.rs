@@ -2,9 +2,11 @@ pub mod commands;
 pub mod models;
 pub mod services;
 
-use crate::models::config::Config;
+use crate::models::config::{Config, ProxySettings};
 use crate::models::error::AppError;
 use crate::models::fabric::{FabricInstaller, FabricLoader, FabricMinecraftVersion};
+use crate::models::liteloader::LiteLoaderManifest;
+use crate::models::quilt::{QuiltLoader, QuiltMinecraftVersion};
 use crate::models::logger::{init_log_bridge, LogLine};
 use crate::services::config::load;
 use log::{error, info};
@@ -12,6 +14,8 @@ use models::versions::MinecraftVersion;
 use services::directory_manager::{
     create_necessary_dirs, get_falcon_launcher_directory,
 };
+use services::download_manager::DownloadManager;
+use services::http_client::HttpClient;
 use services::version_manager::{load_installed_versions};
 use std::collections::{HashMap, VecDeque};
 use std::env;
@@ -34,7 +38,9 @@ pub struct AppState {
     pub launcher_details: FalconLauncher,
     pub log_tx: mpsc::UnboundedSender<LogLine>,
     pub log_history: Arc<Mutex<VecDeque<LogLine>>>,
-    pub process_manager: ProcessManager
+    pub process_manager: ProcessManager,
+    pub http: HttpClient,
+    pub download_manager: DownloadManager,
 }
 pub struct ProcessManager {
     pub active_processes: Mutex<HashMap<String, Mutex<Child>>>,
@@ -49,18 +55,26 @@ impl ProcessManager {
 }
 pub struct Global {
     pub forge: Option<HashMap<String, Vec<String>>>,
+    pub neoforge: Option<Vec<String>>,
     pub fabric_loaders: Option<Vec<FabricLoader>>,
     pub fabric_installers: Option<Vec<FabricInstaller>>,
     pub fabric_mc_versions: Option<Vec<FabricMinecraftVersion>>,
+    pub quilt_loaders: Option<Vec<QuiltLoader>>,
+    pub quilt_mc_versions: Option<Vec<QuiltMinecraftVersion>>,
+    pub liteloader: Option<LiteLoaderManifest>,
     pub versions: Vec<MinecraftVersion>,
 }
 
 pub static GLOBAL_CACHE: LazyLock<sync::Mutex<Global>> = LazyLock::new(|| {
     sync::Mutex::new(Global {
         forge: None,
+        neoforge: None,
         fabric_loaders: None,
         fabric_installers: None,
         fabric_mc_versions: None,
+        quilt_loaders: None,
+        quilt_mc_versions: None,
+        liteloader: None,
         versions: Vec::new(),
     })
 });
@@ -115,8 +129,14 @@ pub fn run() {
 
             let bridge_history = shared_history.clone();
             let log_tx = init_log_bridge(app_handle, bridge_history);
+            let config = load();
+            let http = HttpClient::new(&config.proxy_settings).or_else(|e| {
+                error!("Failed to apply the proxy settings, falling back to the system's: {e}");
+                HttpClient::new(&ProxySettings::default())
+            })?;
+            http.bandwidth().set_limit(config.download_settings.bandwidth_limit);
             app.manage(AppState {
-                config: Arc::new(RwLock::new(load())),
+                config: Arc::new(RwLock::new(config)),
                 launcher_details: FalconLauncher {
                     name: LAUNCHER_NAME.to_string(),
                     version: LAUNCHER_VERSION.to_string(),
@@ -124,6 +144,8 @@ pub fn run() {
                 log_tx,
                 log_history: shared_history,
                 process_manager: ProcessManager::new(),
+                http,
+                download_manager: DownloadManager::new(),
             });
             block_on(async {
                 load_installed_versions().await;
@@ -150,6 +172,8 @@ pub fn run() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::game_launcher::play,
+            commands::game_launcher::get_offline_launch_blockers,
+            commands::game_launcher::verify_version,
             commands::settings::get_maximum_ram_usage,
             commands::settings::get_minimum_ram_usage,
             commands::settings::set_maximum_ram_usage,
@@ -157,9 +181,24 @@ pub fn run() {
             commands::settings::set_language,
             commands::settings::get_language,
             commands::settings::set_exit_on_launch,
+            commands::settings::is_offline_mode,
+            commands::settings::set_offline_mode,
             commands::settings::should_exit_on_launch,
             commands::settings::set_use_dedicated_gpu,
             commands::settings::should_use_dedicated_gpu,
+            commands::settings::set_resolution,
+            commands::settings::get_resolution,
+            commands::settings::set_demo_mode,
+            commands::settings::is_demo_mode,
+            commands::settings::get_max_concurrent_downloads,
+            commands::settings::set_max_concurrent_downloads,
+            commands::settings::get_bandwidth_limit,
+            commands::settings::set_bandwidth_limit,
+            commands::settings::get_job_bandwidth_limit,
+            commands::settings::set_job_bandwidth_limit,
+            commands::settings::get_metadata_cache_ttl,
+            commands::settings::set_metadata_cache_ttl,
+            commands::settings::clear_metadata_cache,
             commands::settings::save,
             commands::settings::set_config,
             commands::settings::get_total_ram,
@@ -171,8 +210,20 @@ pub fn run() {
             commands::downloader::get_versions,
             commands::downloader::reload_version_manifest,
             commands::downloader::download_version,
+            commands::downloader::plan_install,
+            commands::downloader::list_downloads,
+            commands::downloader::pause_download,
+            commands::downloader::resume_download,
+            commands::downloader::cancel_download,
+            commands::downloader::set_download_bandwidth_limit,
+            commands::downloader::clear_finished_downloads,
+            commands::downloader::collect_garbage,
             commands::downloader::get_installed_versions,
             commands::downloader::get_forge_versions,
+            commands::downloader::get_neoforge_versions,
+            commands::downloader::get_quilt_versions,
+            commands::downloader::get_liteloader_versions,
+            commands::downloader::add_optifine,
             commands::downloader::get_fabric_versions,
             commands::downloader::get_vanilla_versions,
             commands::profiles::get_profiles,
@@ -185,6 +236,15 @@ pub fn run() {
             commands::mirrors::get_available_mirrors,
             commands::mirrors::set_mirror,
             commands::mirrors::get_mirror,
+            commands::mirrors::set_mirrors,
+            commands::mirrors::get_mirrors,
+            commands::mirrors::benchmark_mirrors,
+            commands::mirrors::get_mirror_benchmarks,
+            commands::mirrors::set_auto_mirror,
+            commands::mirrors::is_auto_mirror,
+            commands::proxy::get_proxy_settings,
+            commands::proxy::set_proxy_settings,
+            commands::proxy::test_proxy,
             commands::mirrors::import_mirror,
             commands::process_manager::get_processes,
             commands::process_manager::kill_process,
@@ -30,7 +30,19 @@ pub struct LaunchOptions {
     pub ram_usage_min: u64,
     pub ram_usage_max: u64,
     pub use_dedicated_gpu: Bool,
-
+    /// The size of the game's window, it's left to the game unless both are set.
+    #[serde(default)]
+    pub resolution_width: u32,
+    #[serde(default)]
+    pub resolution_height: u32,
+    /// Launches the game as a demo user.
+    #[serde(default)]
+    pub demo_mode: Bool,
+}
+impl LaunchOptions {
+    pub fn has_custom_resolution(&self) -> bool {
+        self.resolution_width > 0 && self.resolution_height > 0
+    }
 }
 impl Default for LaunchOptions {
     fn default() -> Self {
@@ -38,6 +50,9 @@ impl Default for LaunchOptions {
             ram_usage_min: 1024,
             ram_usage_max: 2048,
             use_dedicated_gpu: Bool::TRUE,
+            resolution_width: 0,
+            resolution_height: 0,
+            demo_mode: Bool::FALSE,
         }
     }
 }
@@ -45,7 +60,9 @@ impl Default for LaunchOptions {
 pub struct LauncherSettings {
     pub language: String,
     pub exit_on_launch: Bool,
-
+    /// Launches installed versions from the local files alone, without any network step.
+    #[serde(default)]
+    pub offline_mode: Bool,
 }
 
 impl Default for LauncherSettings {
@@ -53,33 +70,142 @@ impl Default for LauncherSettings {
         Self {
             language: "en".to_string(),
             exit_on_launch: Bool::FALSE,
+            offline_mode: Bool::FALSE,
         }
     }
 }
-mod mirror_serialization {
+/// Mirror lists are stored as a comma separated list of mirror names, a single name (the old format) still works.
+mod mirror_list_serialization {
     use super::*;
-    use serde::{de, Deserializer, Serializer};
+    use serde::{Deserializer, Serializer};
     use crate::models::mirror::mirror_from;
 
-    pub fn serialize<S>(mirror: &Mirror, serializer: S) -> Result<S::Ok, S::Error>
+    pub fn serialize<S>(mirrors: &Vec<Mirror>, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&mirror.name)
+        let names = mirrors.iter().map(|x| x.name.as_str()).collect::<Vec<&str>>();
+        serializer.serialize_str(&names.join(","))
     }
 
-    pub fn deserialize<'de, D>(deserializer: D) -> Result<Mirror, D::Error>
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Mirror>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let name = String::deserialize(deserializer)?;
-        Ok(mirror_from(&name))
+        let names = String::deserialize(deserializer)?;
+        let mut mirrors: Vec<Mirror> = Vec::new();
+        for name in names.split(',').map(|x| x.trim()).filter(|x| !x.is_empty()) {
+            let mirror = mirror_from(&name.to_string());
+            if !mirrors.iter().any(|x| x.name == mirror.name) {
+                mirrors.push(mirror);
+            }
+        }
+        if mirrors.is_empty() {
+            mirrors.push(Mirror::default());
+        }
+        Ok(mirrors)
     }
 }
-#[derive(Debug, Serialize, Deserialize, Default)]
+fn default_mirrors() -> Vec<Mirror> {
+    vec![Mirror::default()]
+}
+fn default_max_concurrent_downloads() -> u64 {
+    8
+}
+fn default_metadata_cache_ttl() -> u64 {
+    60 * 60
+}
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DownloadSettings {
-    #[serde(with = "mirror_serialization")]
-    pub mirror: Mirror,
+    /// Mirrors to download from in order of preference, whenever one fails to serve a file the next one is tried
+    /// and the file's origin is tried last.
+    #[serde(rename = "mirror", with = "mirror_list_serialization", default = "default_mirrors")]
+    pub mirrors: Vec<Mirror>,
+    /// How many files are allowed to be downloaded at the same time.
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: u64,
+    /// Puts the fastest healthy mirror (according to the latest benchmark) first whenever a download starts.
+    #[serde(default)]
+    pub auto_mirror: Bool,
+    /// How many seconds a cached metadata response (version manifest, loader lists...) is served before it gets
+    /// revalidated.
+    #[serde(default = "default_metadata_cache_ttl")]
+    pub metadata_cache_ttl: u64,
+    /// Bytes per second every download shares, 0 means unlimited.
+    #[serde(default)]
+    pub bandwidth_limit: u64,
+    /// Bytes per second each download job starts out with on top of the global cap, 0 means unlimited.
+    #[serde(default)]
+    pub job_bandwidth_limit: u64,
+}
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        Self {
+            mirrors: default_mirrors(),
+            max_concurrent_downloads: default_max_concurrent_downloads(),
+            auto_mirror: Bool::FALSE,
+            metadata_cache_ttl: default_metadata_cache_ttl(),
+            bandwidth_limit: 0,
+            job_bandwidth_limit: 0,
+        }
+    }
+}
+impl DownloadSettings {
+    pub fn concurrency(&self) -> usize {
+        self.max_concurrent_downloads.max(1) as usize
+    }
+
+    /// The most preferred mirror, the one listings and metadata are shown from.
+    pub fn primary_mirror(&self) -> Mirror {
+        self.mirrors.first().cloned().unwrap_or_default()
+    }
+}
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
+pub enum ProxyKind {
+    /// Whatever the `HTTP_PROXY`/`HTTPS_PROXY`/`ALL_PROXY` environment variables say.
+    #[default]
+    SYSTEM,
+    /// Connects directly, ignoring the environment variables as well.
+    NONE,
+    HTTP,
+    HTTPS,
+    SOCKS5,
+}
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProxySettings {
+    pub kind: ProxyKind,
+    pub host: String,
+    pub port: u16,
+    /// Left empty when the proxy doesn't need authentication.
+    pub username: String,
+    pub password: String,
+    /// Comma separated hosts, domains (e.g. `.example.com`) and IP ranges that are reached without the proxy.
+    pub bypass: String,
+}
+impl Default for ProxySettings {
+    fn default() -> Self {
+        Self {
+            kind: ProxyKind::SYSTEM,
+            host: "".to_string(),
+            port: 0,
+            username: "".to_string(),
+            password: "".to_string(),
+            bypass: "".to_string(),
+        }
+    }
+}
+impl ProxySettings {
+    /// The proxy's url, `None` for the kinds that don't name a proxy of their own.
+    pub fn url(&self) -> Option<String> {
+        let scheme = match self.kind {
+            ProxyKind::SYSTEM | ProxyKind::NONE => return None,
+            ProxyKind::HTTP => "http",
+            ProxyKind::HTTPS => "https",
+            // Resolving the hostnames on the proxy's side, the local resolver might be the thing that's blocked.
+            ProxyKind::SOCKS5 => "socks5h",
+        };
+        Some(format!("{scheme}://{}:{}", self.host, self.port))
+    }
 }
 #[derive(Debug, Deserialize, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
@@ -87,10 +213,12 @@ pub struct Config {
     pub launch_options: LaunchOptions,
     pub launcher_settings: LauncherSettings,
     pub download_settings: DownloadSettings,
-    pub native_libraries: NativeLibraries
+    pub native_libraries: NativeLibraries,
+    #[serde(default)]
+    pub proxy_settings: ProxySettings,
 }
 
-#[derive(Debug, Deserialize, Serialize, Default)]
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy)]
 pub enum Bool {
     TRUE,
     #[default]
@@ -126,3 +254,22 @@ impl Config {
         fs::write(get_config_directory(), text).map_err(|x| AppError::FileReadFailed(x.to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrency_defaults_to_eight_workers() {
+        assert_eq!(DownloadSettings::default().concurrency(), 8);
+    }
+
+    #[test]
+    fn concurrency_keeps_at_least_one_worker() {
+        let settings = DownloadSettings {
+            max_concurrent_downloads: 0,
+            ..Default::default()
+        };
+        assert_eq!(settings.concurrency(), 1);
+    }
+}
@@ -0,0 +1,45 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadJobStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl DownloadJobStatus {
+    /// Whether the job is done for good, no matter how it ended.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            DownloadJobStatus::Completed | DownloadJobStatus::Failed | DownloadJobStatus::Cancelled
+        )
+    }
+}
+
+/// Snapshot of a download job, emitted through the `download-progress` event and returned by `list_downloads`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub task_id: String,
+    pub version: String,
+    pub name: String,
+    pub status: DownloadJobStatus,
+    pub stage: String,
+    pub current_file: String,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub files_done: u64,
+    pub files_total: u64,
+    /// Bytes per second.
+    pub speed: u64,
+    /// Estimated seconds left, if the speed is known.
+    pub eta: Option<u64>,
+    /// The job's own cap in bytes per second, 0 when it's only bound by the global one.
+    pub bandwidth_limit: u64,
+    pub error: Option<String>,
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::path::PathBuf;
 use serde_json::Value;
 use crate::models::versions::VersionType;
 use crate::models::versions::VersionBase;
@@ -26,6 +27,12 @@ pub struct AssetIndex {
 #[derive(Deserialize, Debug)]
 pub struct AssetObjects {
     pub objects: HashMap<String, AssetEntry>,
+    /// Pre-1.7 indexes, the game reads the objects by their names from `assets/virtual/<id>`.
+    #[serde(default, rename = "virtual")]
+    pub is_virtual: bool,
+    /// Even older indexes, the game reads the objects by their names from its own `resources` directory.
+    #[serde(default)]
+    pub map_to_resources: bool,
 }
 
 #[derive(Deserialize, Debug)]
@@ -34,6 +41,26 @@ pub struct AssetEntry {
     pub size: u64,
 }
 
+/// A single file that has to be fetched, alongside what it should look like once it's on the disk.
+#[derive(Debug, Clone)]
+pub struct DownloadTask {
+    pub url: String,
+    pub path: PathBuf,
+    pub sha1: String,
+    pub size: u64,
+}
+
+impl DownloadTask {
+    pub fn new(url: String, path: PathBuf, sha1: &str, size: u64) -> Self {
+        Self {
+            url,
+            path,
+            sha1: sha1.to_string(),
+            size,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LibraryInfo {
     pub name: String,
@@ -42,11 +69,6 @@ pub struct LibraryInfo {
     pub url: String,
 }
 
-pub struct LibraryRules {
-    pub allowed_oses: Vec<String>,
-    pub disallowed_oses: Vec<String>,
-}
-
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct LoggingClient {
@@ -109,15 +131,19 @@ pub struct JavaVersion {
     pub major_version: u32,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct RuleOS {
     pub name: Option<String>,
+    pub arch: Option<String>,
+    /// A regex the os release has to match.
+    pub version: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct Rule {
     pub action: String,
     pub os: Option<RuleOS>,
+    pub features: Option<HashMap<String, bool>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -126,6 +152,20 @@ pub struct Library {
     pub downloads: Option<LibraryDownloads>,
     pub rules: Option<Vec<Rule>>,
     pub url: Option<String>,
+    /// Maven style libraries (e.g. Fabric's) carry their hash and size next to the repository url.
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+    /// The natives classifier of each os, `${arch}` stands for the bitness ("32" or "64").
+    pub natives: Option<HashMap<String, String>>,
+    pub extract: Option<ExtractRules>,
+}
+
+/// How a natives jar gets extracted.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ExtractRules {
+    /// Prefixes of the paths that are left in the jar, e.g. `META-INF/`.
+    #[serde(default)]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -177,12 +217,15 @@ impl VersionLoader {
                 let forge_ver = args[1].split("-").last().unwrap();
                 format!("{}-forge-{}", vanilla_id, forge_ver)
             }
-            VersionBase::NEOFORGE => self.id.clone(),
+            VersionBase::NEOFORGE => format!("neoforge-{}", self.id),
             FABRIC => {
                 let args = self.id.split("-").collect::<Vec<_>>();
                 format!("fabric-loader-{}-{}", args[1], args[0])
             }
             VersionBase::LITELOADER => self.id.clone(),
+            VersionBase::QUILT => {
+                format!("quilt-loader-{}-{}", self.get_quilt_loader_id(), self.get_quilt_version_id())
+            }
         }
     }
     pub fn get_fabric_loader_id(&self) -> String {
@@ -191,6 +234,14 @@ impl VersionLoader {
     pub fn get_fabric_version_id(&self) -> String {
         self.id.split("-").collect::<Vec<&str>>()[0].to_string()
     }
+    /// Quilt's loader versions carry their pre-release in a dash (e.g. `0.26.4-beta.1`), so only the first one
+    /// separates it from the Minecraft version.
+    pub fn get_quilt_loader_id(&self) -> String {
+        self.id.split_once("-").map(|x| x.1).unwrap_or_default().to_string()
+    }
+    pub fn get_quilt_version_id(&self) -> String {
+        self.id.split_once("-").map(|x| x.0).unwrap_or(&self.id).to_string()
+    }
 }
 
 // Models designed specifically for legacy Forge installer profile JSON extraction
@@ -257,6 +308,46 @@ pub struct ForgeArtifact {
     pub sha1: Option<String>,
 }
 
+/// The `install_profile.json` of modern (1.13+) Forge installers, the version JSON sits next to it in the jar and
+/// the patched game jars are produced by running its processors.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeInstallProfileV1 {
+    pub spec: Option<u32>,
+    /// The id of the version it installs, e.g. `1.20.1-forge-47.2.0`.
+    pub version: String,
+    pub minecraft: String,
+    /// Where the version JSON is inside the installer jar.
+    pub json: String,
+    #[serde(default)]
+    pub data: HashMap<String, ForgeSidedData>,
+    #[serde(default)]
+    pub processors: Vec<ForgeProcessor>,
+    #[serde(default)]
+    pub libraries: Vec<ForgeLibrary>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ForgeSidedData {
+    pub client: String,
+    pub server: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ForgeProcessor {
+    /// The maven coordinates of the jar that runs, its manifest names the main class.
+    pub jar: String,
+    #[serde(default)]
+    pub classpath: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// The files the processor produces mapped to their SHA1, both may refer to the profile's data.
+    #[serde(default)]
+    pub outputs: HashMap<String, String>,
+    /// The sides it runs on, every side when missing.
+    pub sides: Option<Vec<String>>,
+}
+
 // helper converter to adapter from Library struct
 pub fn library_from_value_legacy(value: &Value) -> LibraryInfo {
 
@@ -52,12 +52,46 @@ pub enum AppError {
     #[error("Network Error: {0}")]
     NetworkRequestFailed(String),
 
+    #[error("HTTP Error {0}: {1}")]
+    HttpStatus(u16, String),
+
+    #[error("Request Timed Out: {0}")]
+    RequestTimedOut(String),
+
+    #[error("Connection Failed: {0}")]
+    ConnectionFailed(String),
+
     #[error("Download Failed")]
     DownloadFailed,
 
+    #[error("File Verification Failed: {0}")]
+    FileVerificationFailed(String),
+
+    #[error("Download Cancelled: {0}")]
+    DownloadCancelled(String),
+
+    #[error("Download Job Not Found: {0}")]
+    DownloadJobNotFound(String),
+
     #[error("Not Implemented: {0}")]
     NotImplemented(String),
 
+    #[error("Downloads are still in progress")]
+    DownloadsInProgress,
+
+    #[error("Invalid Proxy: {0}")]
+    InvalidProxy(String),
+
+    #[error("Forge Processor Failed: {0}")]
+    ForgeProcessorFailed(String),
+
+    #[error("OptiFine Install Failed: {0}")]
+    OptiFineInstallFailed(String),
+
+    /// Holds the paths of the files the launch can't go without.
+    #[error("Offline Launch Blocked, missing: {}", .0.join(", "))]
+    OfflineLaunchBlocked(Vec<String>),
+
     #[error(transparent)]
     Io(#[from] std::io::Error),
 
@@ -143,8 +177,19 @@ impl Serialize for AppError {
             AppError::AccessDenied(e) => ("ERROR_ACCESS_DENIED", Some(e.to_string())),
             AppError::LogHistoryNotFound => ("ERROR_LOG_HISTORY_NOT_FOUND", None),
             AppError::NetworkRequestFailed(e) => ("ERROR_NETWORK_REQUEST_FAILED", Some(e.to_string())),
+            AppError::HttpStatus(status, url) => ("ERROR_HTTP_STATUS", Some(format!("{url} responded with {status}"))),
+            AppError::RequestTimedOut(e) => ("ERROR_REQUEST_TIMED_OUT", Some(e.to_string())),
+            AppError::ConnectionFailed(e) => ("ERROR_CONNECTION_FAILED", Some(e.to_string())),
             AppError::DownloadFailed => ("ERROR_DOWNLOAD_FAILED", None),
+            AppError::FileVerificationFailed(e) => ("ERROR_FILE_VERIFICATION_FAILED", Some(e.to_string())),
+            AppError::DownloadCancelled(e) => ("ERROR_DOWNLOAD_CANCELLED", Some(e.to_string())),
+            AppError::DownloadJobNotFound(e) => ("ERROR_DOWNLOAD_JOB_NOT_FOUND", Some(e.to_string())),
             AppError::NotImplemented(e) => ("ERROR_NOT_IMPLEMENTED", Some(e.to_string())),
+            AppError::DownloadsInProgress => ("ERROR_DOWNLOADS_IN_PROGRESS", None),
+            AppError::InvalidProxy(e) => ("ERROR_INVALID_PROXY", Some(e.to_string())),
+            AppError::ForgeProcessorFailed(e) => ("ERROR_FORGE_PROCESSOR_FAILED", Some(e.to_string())),
+            AppError::OptiFineInstallFailed(e) => ("ERROR_OPTIFINE_INSTALL_FAILED", Some(e.to_string())),
+            AppError::OfflineLaunchBlocked(files) => ("ERROR_OFFLINE_LAUNCH_BLOCKED", Some(files.join("\n"))),
             
             AppError::Io(e) => ("ERROR_FILE_READ_FAILED", Some(e.to_string())), // Fallback mapping
             AppError::Anyhow(e) => ("ERROR_INTERNAL", Some(e.to_string())),
@@ -0,0 +1,40 @@
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum GarbageKind {
+    Library,
+    AssetIndex,
+    Asset,
+    /// A whole Java runtime component directory.
+    JavaRuntime,
+    /// An object of the shared store that nothing links to anymore.
+    StoredObject,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GarbageFile {
+    pub kind: GarbageKind,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// The files no installed version refers to anymore.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GarbageReport {
+    pub files: Vec<GarbageFile>,
+    /// The total size of the files, the space deleting them gives back.
+    pub reclaimable_bytes: u64,
+    /// Whether the files were deleted or only reported.
+    pub deleted: bool,
+}
+
+impl GarbageReport {
+    pub fn push(&mut self, kind: GarbageKind, path: PathBuf, size: u64) {
+        self.reclaimable_bytes += size;
+        self.files.push(GarbageFile { kind, path, size });
+    }
+}
@@ -0,0 +1,220 @@
+use crate::models::downloader::DownloadTask;
+use crate::services::utils::{verify_file_existence_with_sha, verify_file_existence_with_size};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PlannedFileKind {
+    VersionJson,
+    Installer,
+    JavaRuntime,
+    Library,
+    Native,
+    Client,
+    Logging,
+    AssetIndex,
+    Asset,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedFile {
+    pub kind: PlannedFileKind,
+    pub url: String,
+    pub path: PathBuf,
+    pub sha1: String,
+    pub size: u64,
+    /// Whether the file is already on the disk and matches its SHA1 (or size when there's no hash), only known once
+    /// `InstallPlan::check_installed` ran.
+    pub installed: bool,
+    /// Prefixes of the paths a natives jar keeps to itself when it's extracted.
+    pub exclude: Vec<String>,
+}
+
+impl PlannedFile {
+    pub fn new(kind: PlannedFileKind, task: DownloadTask) -> Self {
+        Self {
+            kind,
+            url: task.url,
+            path: task.path,
+            sha1: task.sha1,
+            size: task.size,
+            installed: false,
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Reads the whole file to compare its SHA1, or only its size when there's no hash.
+    fn is_on_disk(&self) -> bool {
+        if self.sha1.is_empty() {
+            verify_file_existence_with_size(&self.path.to_string_lossy().into_owned(), self.size)
+        } else {
+            verify_file_existence_with_sha(&self.path, &self.sha1)
+        }
+        .unwrap_or(false)
+    }
+
+    pub fn to_task(&self) -> DownloadTask {
+        DownloadTask::new(self.url.clone(), self.path.clone(), &self.sha1, self.size)
+    }
+}
+
+/// A Java runtime the plan depends on, its `release` file gets written once the runtime's files are in place.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedRuntime {
+    pub component: String,
+    pub version: String,
+}
+
+/// Everything an installation is going to fetch. planning doesn't look at the files themselves, which of them are
+/// installed is only known after `check_installed`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallPlan {
+    pub version: String,
+    pub files: Vec<PlannedFile>,
+    /// The paths of `files`, so planning the same file twice is caught without going through all of them.
+    #[serde(skip)]
+    paths: HashSet<PathBuf>,
+    pub runtimes: Vec<PlannedRuntime>,
+    /// The size of every file in the plan, installed or not.
+    pub total_bytes: u64,
+    /// The size of the files that still have to be downloaded, as of the last `check_installed`.
+    pub download_bytes: u64,
+    /// Parts of the installation that can't be resolved ahead of time, e.g. files generated by a loader's installer.
+    pub notes: Vec<String>,
+}
+
+impl InstallPlan {
+    pub fn new(version: String) -> Self {
+        Self {
+            version,
+            files: Vec::new(),
+            paths: HashSet::new(),
+            runtimes: Vec::new(),
+            total_bytes: 0,
+            download_bytes: 0,
+            notes: Vec::new(),
+        }
+    }
+
+    /// Adds a file to the plan, files that are already planned for the same path are ignored.
+    pub fn push(&mut self, kind: PlannedFileKind, task: DownloadTask) {
+        if !self.paths.insert(task.path.clone()) {
+            return;
+        }
+        let file = PlannedFile::new(kind, task);
+        self.total_bytes += file.size;
+        self.files.push(file);
+    }
+
+    /// Adds a natives jar to the plan, to be extracted without the paths starting with any of `exclude`.
+    pub fn push_native(&mut self, task: DownloadTask, exclude: Vec<String>) {
+        let path = task.path.clone();
+        self.push(PlannedFileKind::Native, task);
+        if let Some(file) = self.files.iter_mut().find(|x| x.path == path && x.kind == PlannedFileKind::Native) {
+            file.exclude = exclude;
+        }
+    }
+
+    pub fn add_runtime(&mut self, component: &str, version: &str) {
+        let runtime = PlannedRuntime {
+            component: component.to_string(),
+            version: version.to_string(),
+        };
+        if !self.runtimes.contains(&runtime) {
+            self.runtimes.push(runtime);
+        }
+    }
+
+    /// Merges another plan (e.g. the vanilla version a loader inherits from) into this one.
+    pub fn extend(&mut self, other: InstallPlan) {
+        for file in other.files {
+            if !self.paths.insert(file.path.clone()) {
+                continue;
+            }
+            self.total_bytes += file.size;
+            if !file.installed {
+                self.download_bytes += file.size;
+            }
+            self.files.push(file);
+        }
+        for runtime in other.runtimes {
+            self.add_runtime(&runtime.component, &runtime.version);
+        }
+        self.notes.extend(other.notes);
+    }
+
+    /// Finds out which files are already on the disk. every file with a SHA1 gets read and hashed in full, so it's a
+    /// pass over the whole installation.
+    pub fn check_installed(&mut self) {
+        self.download_bytes = 0;
+        for file in &mut self.files {
+            file.installed = file.is_on_disk();
+            if !file.installed {
+                self.download_bytes += file.size;
+            }
+        }
+    }
+
+    /// The files of the given kinds that aren't installed yet, see `check_installed`.
+    pub fn missing(&self, kinds: &[PlannedFileKind]) -> Vec<DownloadTask> {
+        self.files
+            .iter()
+            .filter(|x| !x.installed && kinds.contains(&x.kind))
+            .map(|x| x.to_task())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(path: &str, size: u64) -> DownloadTask {
+        DownloadTask::new(String::new(), PathBuf::from(path), "", size)
+    }
+
+    #[test]
+    fn push_ignores_paths_already_planned() {
+        let mut plan = InstallPlan::new("1.20.1".to_string());
+        plan.push(PlannedFileKind::Library, task("libraries/a.jar", 10));
+        plan.push(PlannedFileKind::Native, task("libraries/a.jar", 10));
+        plan.push(PlannedFileKind::Library, task("libraries/b.jar", 5));
+        assert_eq!(plan.files.len(), 2);
+        assert_eq!(plan.files[0].kind, PlannedFileKind::Library);
+        assert_eq!(plan.total_bytes, 15);
+    }
+
+    #[test]
+    fn extend_merges_without_duplicates() {
+        let mut plan = InstallPlan::new("fabric-loader-0.16.0-1.20.1".to_string());
+        plan.push(PlannedFileKind::Library, task("libraries/a.jar", 10));
+        let mut vanilla = InstallPlan::new("1.20.1".to_string());
+        vanilla.push(PlannedFileKind::Library, task("libraries/a.jar", 10));
+        vanilla.push(PlannedFileKind::Client, task("versions/1.20.1/1.20.1.jar", 20));
+        vanilla.add_runtime("java-runtime-gamma", "17");
+        plan.extend(vanilla);
+
+        assert_eq!(plan.files.len(), 2);
+        assert_eq!(plan.total_bytes, 30);
+        assert_eq!(plan.runtimes.len(), 1);
+        plan.push(PlannedFileKind::Client, task("versions/1.20.1/1.20.1.jar", 20));
+        assert_eq!(plan.files.len(), 2);
+    }
+
+    #[test]
+    fn files_count_as_missing_until_checked() {
+        let mut plan = InstallPlan::new("1.20.1".to_string());
+        plan.push(PlannedFileKind::Library, task("/nonexistent/falcon/a.jar", 10));
+        assert!(!plan.files[0].installed);
+
+        plan.check_installed();
+        assert_eq!(plan.download_bytes, 10);
+        assert_eq!(plan.missing(&[PlannedFileKind::Library]).len(), 1);
+        assert!(plan.missing(&[PlannedFileKind::Asset]).is_empty());
+    }
+}
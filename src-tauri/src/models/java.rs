@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use crate::models::platform;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,14 +24,18 @@ impl Java {
         Java { path, version }
     }
     pub fn get_bin_file(&self) -> PathBuf {
+        Java::bin_file_of(&self.path)
+    }
+    /// The binary of the runtime installed at `path`, usable before the runtime is known to be there.
+    pub fn bin_file_of(path: &Path) -> PathBuf {
         let os = platform::get_current_os();
 
         if os == "windows" {
-            self.path
+            path
                 .join("bin")
                 .join("javaw.exe")
         } else {
-                self.path
+            path
                 .join("bin")
                 .join("java")
         }
@@ -0,0 +1,66 @@
+use crate::models::platform::{get_current_arch, get_current_os};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Where the game should take the player right away, only versions with quick play support (1.20+) honor it.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "type", content = "target", rename_all = "camelCase")]
+pub enum QuickPlay {
+    /// The name of the world's folder.
+    Singleplayer(String),
+    /// The address of the server, with its port if it's not the default one.
+    Multiplayer(String),
+    /// The id of the realm.
+    Realms(String),
+}
+
+impl QuickPlay {
+    /// The feature of the version JSONs that enables this kind of quick play.
+    pub fn feature(&self) -> &'static str {
+        match self {
+            QuickPlay::Singleplayer(_) => "is_quick_play_singleplayer",
+            QuickPlay::Multiplayer(_) => "is_quick_play_multiplayer",
+            QuickPlay::Realms(_) => "is_quick_play_realms",
+        }
+    }
+
+    pub fn target(&self) -> &str {
+        match self {
+            QuickPlay::Singleplayer(x) | QuickPlay::Multiplayer(x) | QuickPlay::Realms(x) => x,
+        }
+    }
+}
+
+/// What Mojang's rules get checked against: the machine the game runs on and the features the launch enables.
+#[derive(Debug, Clone)]
+pub struct LaunchContext {
+    /// Mojang's name of the os, "osx", "linux" or "windows".
+    pub os: String,
+    /// Mojang's name of the architecture, e.g. "x86" or "arm64".
+    pub arch: String,
+    /// The os release, matched against the `os.version` regexes.
+    pub os_version: String,
+    /// Features such as `is_demo_user` or `has_custom_resolution`, the ones that aren't in here count as disabled.
+    pub features: HashMap<String, bool>,
+}
+
+impl LaunchContext {
+    /// The context of the machine the launcher runs on, with no features enabled.
+    pub fn current() -> Self {
+        Self {
+            os: get_current_os(),
+            arch: get_current_arch(),
+            os_version: sys_info::os_release().unwrap_or_default(),
+            features: HashMap::new(),
+        }
+    }
+
+    pub fn with_feature(mut self, feature: &str, enabled: bool) -> Self {
+        self.features.insert(feature.to_string(), enabled);
+        self
+    }
+
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.get(feature).copied().unwrap_or(false)
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// LiteLoader's `versions.json`, keyed by Minecraft version.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LiteLoaderManifest {
+    pub versions: HashMap<String, LiteLoaderMinecraftVersion>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LiteLoaderMinecraftVersion {
+    pub repo: Option<LiteLoaderRepo>,
+    /// Release builds.
+    pub artefacts: Option<LiteLoaderBranch>,
+    pub snapshots: Option<LiteLoaderBranch>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LiteLoaderBranch {
+    /// Overrides the repository of the Minecraft version, snapshots may be hosted elsewhere.
+    pub repo: Option<LiteLoaderRepo>,
+    /// The builds by version, along with a `latest` alias of the newest one.
+    #[serde(rename = "com.mumfrey:liteloader", default)]
+    pub builds: HashMap<String, LiteLoaderBuild>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LiteLoaderRepo {
+    pub stream: Option<String>,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LiteLoaderBuild {
+    pub version: String,
+    pub file: Option<String>,
+    pub tweak_class: String,
+    #[serde(default)]
+    pub libraries: Vec<LiteLoaderLibrary>,
+    pub timestamp: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LiteLoaderLibrary {
+    pub name: String,
+    pub url: Option<String>,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// A metadata response (version manifest, loader lists...) kept on the disk alongside what's needed to revalidate it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedMetadata {
+    /// The origin url the response belongs to, whichever mirror ended up serving it.
+    pub url: String,
+    /// The url that actually served the response, its validators only mean something to that server.
+    #[serde(default)]
+    pub source: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Unix timestamp (in seconds) of when the response was last fetched or revalidated.
+    pub fetched_at: u64,
+    pub body: String,
+}
@@ -1,7 +1,7 @@
 use crate::models::error::AppError;
 use crate::services::directory_manager::get_mirrors_dir;
 use log::info;
-use reqwest::Client;
+use crate::services::http_client::HttpClient;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::fs;
@@ -12,7 +12,72 @@ pub struct Mirror {
     pub name: String,
     pub description: String,
     pub maps: HashMap<String, String>,
+    /// Replacements for the loader and mod hosts, left empty for the ones the mirror doesn't cover.
+    #[serde(default)]
+    pub hosts: MirrorHosts,
+    /// Applied before the host maps, for mirrors that lay some files out differently than their origin.
+    #[serde(default)]
+    pub rewrites: Vec<PathRewrite>,
 }
+
+/// The named host groups a mirror can cover on top of Mojang's domains.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorHosts {
+    pub fabric_meta: Option<String>,
+    pub fabric_maven: Option<String>,
+    pub forge_maven: Option<String>,
+    pub forge_files: Option<String>,
+    pub neoforge_maven: Option<String>,
+    pub quilt_meta: Option<String>,
+    pub quilt_maven: Option<String>,
+    pub modrinth_api: Option<String>,
+    pub modrinth_cdn: Option<String>,
+}
+
+impl MirrorHosts {
+    /// Pairs every covered origin host with its replacement.
+    pub fn entries(&self) -> Vec<(&'static str, &String)> {
+        [
+            ("https://meta.fabricmc.net/", &self.fabric_meta),
+            ("https://maven.fabricmc.net/", &self.fabric_maven),
+            ("https://maven.minecraftforge.net/", &self.forge_maven),
+            ("https://files.minecraftforge.net/", &self.forge_files),
+            ("https://maven.neoforged.net/", &self.neoforge_maven),
+            ("https://meta.quiltmc.org/", &self.quilt_meta),
+            ("https://maven.quiltmc.org/", &self.quilt_maven),
+            ("https://api.modrinth.com/", &self.modrinth_api),
+            ("https://cdn.modrinth.com/", &self.modrinth_cdn),
+        ]
+        .into_iter()
+        .filter_map(|(origin, host)| host.as_ref().map(|x| (origin, x)))
+        .collect()
+    }
+}
+
+/// Replaces the `from` prefix of a url with `to`, e.g. to move a file the mirror serves from another path.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PathRewrite {
+    pub from: String,
+    pub to: String,
+}
+
+/// How a mirror did the last time it was benchmarked.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorBenchmark {
+    pub name: String,
+    /// Average time until the response headers arrived, across the mirror's hosts.
+    pub latency_ms: Option<u64>,
+    /// Bytes per second, measured on the probe files that were fully downloaded.
+    pub throughput: Option<u64>,
+    /// Whether every host of the mirror answered its probe.
+    pub healthy: bool,
+    pub error: Option<String>,
+    /// Unix timestamp (in seconds) of when the benchmark was taken.
+    pub tested_at: u64,
+}
+
 impl Default for Mirror {
     fn default() -> Self {
         mojang_mirror()
@@ -39,22 +104,44 @@ impl Mirror {
 
         let https_domain = format!("https://{domain}/");
 
-        if !self.maps.contains_key(https_domain.as_str()) {
-            return url.clone();
+        if let Some(rewrite) = self.rewrites.iter().find(|x| url.starts_with(&x.from)) {
+            return url.replacen(&rewrite.from, &rewrite.to, 1);
         }
-        if self.maps.contains_key(https_domain.as_str()) {
-            url.replace(https_domain.as_str(), &*self.maps[&https_domain])
-        } else {
-            url.clone()
+        match self.host_for(&https_domain) {
+            Some(host) => url.replacen(https_domain.as_str(), host, 1),
+            None => url.clone(),
         }
     }
-    pub async fn is_connected(&self) -> bool {
+
+    fn host_for(&self, https_domain: &str) -> Option<&String> {
+        self.maps.get(https_domain).or_else(|| {
+            self.hosts
+                .entries()
+                .into_iter()
+                .find(|(origin, _)| *origin == https_domain)
+                .map(|(_, host)| host)
+        })
+    }
+
+    /// Every origin host the mirror covers alongside its replacement.
+    pub fn all_hosts(&self) -> Vec<(String, String)> {
+        let mut hosts: Vec<(String, String)> = self.maps.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        hosts.extend(
+            self.hosts
+                .entries()
+                .into_iter()
+                .map(|(origin, host)| (origin.to_string(), host.clone())),
+        );
+        hosts
+    }
+    pub async fn is_connected(&self, client: &HttpClient) -> bool {
         let mut t = true;
-        for url in self.maps.values() {
-            let Ok(client) = Client::builder().timeout(Duration::from_secs(3)).build() else {
-                return false;
-            };
-            let Ok(_) = client.head(url).send().await else {
+        for (_, url) in self.all_hosts() {
+            let request = client
+                .without_retries()
+                .head(url)
+                .timeout(Duration::from_secs(3));
+            let Ok(_) = request.send().await else {
                 info!("Failed to connect to the mirror's url");
                 t = false;
                 break;
@@ -98,6 +185,8 @@ pub fn mirror(
         name,
         description,
         maps,
+        hosts: MirrorHosts::default(),
+        rewrites: Vec::new(),
     }
 }
 pub fn ninecraft_mirror() -> Mirror {
@@ -167,3 +256,54 @@ pub fn mirror_from(name: &String) -> Mirror {
             .clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn covering_mirror() -> Mirror {
+        let mut mirror = ninecraft_mirror();
+        mirror.hosts.forge_maven = Some("https://forge.example.com/".to_string());
+        mirror.rewrites.push(PathRewrite {
+            from: "https://maven.minecraftforge.net/net/minecraftforge/forge/".to_string(),
+            to: "https://files.example.com/forge/".to_string(),
+        });
+        mirror
+    }
+
+    #[test]
+    fn parse_url_maps_mojang_hosts() {
+        let url = "https://libraries.minecraft.net/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1.jar".to_string();
+        assert_eq!(
+            ninecraft_mirror().parse_url(&url),
+            "https://libraries-minecraft.9craft.ir/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1.jar"
+        );
+    }
+
+    #[test]
+    fn parse_url_upgrades_http() {
+        let url = "http://resources.download.minecraft.net/ab/abcdef".to_string();
+        assert_eq!(ninecraft_mirror().parse_url(&url), "https://resources-download.9craft.ir/ab/abcdef");
+    }
+
+    #[test]
+    fn parse_url_applies_rewrites_before_hosts() {
+        let url = "https://maven.minecraftforge.net/net/minecraftforge/forge/1.20.1-47.2.0/forge.jar".to_string();
+        assert_eq!(covering_mirror().parse_url(&url), "https://files.example.com/forge/1.20.1-47.2.0/forge.jar");
+    }
+
+    #[test]
+    fn parse_url_falls_back_to_hosts_when_no_rewrite_matches() {
+        let url = "https://maven.minecraftforge.net/cpw/mods/securejarhandler/2.1.10/securejarhandler-2.1.10.jar";
+        assert_eq!(
+            covering_mirror().parse_url(&url.to_string()),
+            "https://forge.example.com/cpw/mods/securejarhandler/2.1.10/securejarhandler-2.1.10.jar"
+        );
+    }
+
+    #[test]
+    fn parse_url_leaves_uncovered_hosts_alone() {
+        let url = "https://maven.neoforged.net/releases/net/neoforged/neoforge/21.0.1/neoforge.jar".to_string();
+        assert_eq!(covering_mirror().parse_url(&url), url);
+    }
+}
@@ -2,6 +2,9 @@ pub mod config;
 pub mod error;
 pub mod mods;
 pub mod fabric;
+pub mod quilt;
+pub mod neoforge;
+pub mod liteloader;
 pub mod versions;
 pub mod mirror;
 pub mod profiles;
@@ -10,4 +13,10 @@ pub mod platform;
 pub mod java;
 pub mod logger;
 pub mod utils;
-pub mod modrinth;
\ No newline at end of file
+pub mod modrinth;
+pub mod download_job;
+pub mod install_plan;
+pub mod metadata;
+pub mod garbage;
+pub mod verification;
+pub mod launch_context;
@@ -41,6 +41,26 @@ pub struct FabricModInfo {
     #[serde(rename = "updateUrl")]
     pub update_url: Option<String>,
 }
+/// `quilt.mod.json`, of which only the loader block describes the mod itself.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuiltModJson {
+    pub schema_version: u32,
+    pub quilt_loader: QuiltModInfo,
+}
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuiltModInfo {
+    pub group: Option<String>,
+    #[serde(rename = "id")]
+    pub mod_id: String,
+    pub version: String,
+    #[serde(default)]
+    pub metadata: QuiltModMetadata,
+}
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct QuiltModMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct FabricModInfoContact {
@@ -80,4 +100,35 @@ impl ModInfo {
             enabled: true,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quilt_mod_json_takes_the_loader_block() {
+        let content = r#"{
+            "schema_version": 1,
+            "quilt_loader": {
+                "group": "org.quiltmc",
+                "id": "qsl",
+                "version": "6.1.2",
+                "metadata": { "name": "Quilt Standard Libraries" },
+                "entrypoints": {}
+            }
+        }"#;
+        let info = serde_json::from_str::<QuiltModJson>(content).unwrap().quilt_loader;
+        assert_eq!(info.mod_id, "qsl");
+        assert_eq!(info.version, "6.1.2");
+        assert_eq!(info.metadata.name.as_deref(), Some("Quilt Standard Libraries"));
+        assert_eq!(info.metadata.description, None);
+    }
+
+    #[test]
+    fn quilt_mod_json_metadata_is_optional() {
+        let content = r#"{ "schema_version": 1, "quilt_loader": { "id": "example", "version": "1.0.0" } }"#;
+        let info = serde_json::from_str::<QuiltModJson>(content).unwrap().quilt_loader;
+        assert_eq!(info.metadata.name, None);
+    }
+}
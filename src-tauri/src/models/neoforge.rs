@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// The versions NeoForge's maven lists for an artifact, oldest first.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NeoForgeVersions {
+    #[serde(rename = "isSnapshot", default)]
+    pub is_snapshot: bool,
+    pub versions: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_read_from_the_maven_api() {
+        let content = r#"{ "isSnapshot": false, "versions": ["20.2.3-beta", "21.1.77"] }"#;
+        let versions = serde_json::from_str::<NeoForgeVersions>(content).unwrap();
+        assert_eq!(versions.versions, vec!["20.2.3-beta", "21.1.77"]);
+    }
+}
@@ -14,14 +14,31 @@ pub fn parse_os(os: String) -> String {
 pub fn get_current_os() -> String {
     parse_os(sys_info::os_type().expect("Unsupported Operating System"))
 }
-pub fn get_current_os_with_architecture() -> String{
-    let mut os = parse_os(sys_info::os_release().expect("Unsupported Operating System"));
-     if os == "windows" {
-         "windows-x64".to_string() //FOR NOW "
-    }else if os == "osx" {
-         "mac-os".to_string() // FOR NOW
-    }else {
-         "linux".to_string()  // FOR NOW
+/// Returns the architecture the launcher is running on, named the way Mojang's rules name it.
+///
+/// Such as "x86", "x86_64", "arm64"
+pub fn get_current_arch() -> String {
+    match std::env::consts::ARCH {
+        "aarch64" => "arm64".to_string(),
+        "arm" => "arm32".to_string(),
+        arch => arch.to_string(),
+    }
+}
 
+/// Returns the key Mojang's Java runtimes list uses for the current os and architecture.
+///
+/// Such as "linux", "linux-i386", "mac-os-arm64", "windows-x64". Mojang has no builds for some of them (e.g.
+/// "linux-arm64"), their keys just aren't in the list.
+pub fn get_current_os_with_architecture() -> String {
+    let arch = get_current_arch();
+    match (get_current_os().as_str(), arch.as_str()) {
+        ("windows", "x86") => "windows-x86".to_string(),
+        ("windows", "arm64") => "windows-arm64".to_string(),
+        ("windows", _) => "windows-x64".to_string(),
+        ("osx", "arm64") => "mac-os-arm64".to_string(),
+        ("osx", _) => "mac-os".to_string(),
+        (_, "x86") => "linux-i386".to_string(),
+        (_, "x86_64") => "linux".to_string(),
+        (_, arch) => format!("linux-{arch}"),
     }
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QuiltLoader {
+    pub separator: String,
+    pub build: u32,
+    pub maven: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QuiltMinecraftVersion {
+    pub version: String,
+    pub stable: bool,
+}
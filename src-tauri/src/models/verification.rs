@@ -0,0 +1,30 @@
+use crate::models::install_plan::PlannedFile;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// What checking an installed version against its manifests found, nothing in it has been repaired.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationReport {
+    pub version: String,
+    /// How many files were checked.
+    pub checked: usize,
+    /// Files that aren't on the disk at all.
+    pub missing: Vec<PlannedFile>,
+    /// Files that are on the disk but don't match their SHA1 (or size when there's no hash).
+    pub corrupt: Vec<PlannedFile>,
+    /// Files sitting where only known files should be, e.g. in a Java runtime or the extracted natives.
+    pub extra: Vec<PathBuf>,
+}
+
+impl VerificationReport {
+    pub fn new(version: String) -> Self {
+        Self {
+            version,
+            checked: 0,
+            missing: Vec::new(),
+            corrupt: Vec::new(),
+            extra: Vec::new(),
+        }
+    }
+}
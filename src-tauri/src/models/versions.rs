@@ -1,8 +1,10 @@
 use crate::models::downloader;
 use crate::models::downloader::{MinecraftManifestVersion, VersionLoader};
+use crate::models::launch_context::LaunchContext;
 use crate::models::platform::get_current_os;
 use crate::services::directory_manager::{get_libraries_directory, get_versions_directory};
-use crate::services::utils::{extend_once, parse_library_name_to_path};
+use crate::services::rule_engine::is_allowed_value;
+use crate::services::utils::{arm64_natives_path, extend_once, parse_library_name_to_path};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fs;
@@ -135,7 +137,11 @@ impl MinecraftVersion {
             return libraries;
         };
 
+        let context = LaunchContext::current();
         for library in library_array {
+            if !is_allowed_value(library.get("rules"), &context) {
+                continue;
+            }
             if library.get("downloads").is_none() || library["downloads"].is_null() {
                 if let Some(library_name) = library.get("name").and_then(|n| n.as_str()) {
                     if let Ok(mut library_path_str) = parse_library_name_to_path(library_name.to_string()) {
@@ -184,8 +190,13 @@ impl MinecraftVersion {
             let library_info = downloader::library_from_value_legacy(library);
             let os = get_current_os();
 
+            // LWJGL loads the natives it ships as artifacts from the classpath, so the stand-in has to be there.
+            let name = library.get("name").and_then(|n| n.as_str()).unwrap_or_default();
+            let classifier = name.split(':').nth(3).unwrap_or_default();
+            let library_path = arm64_natives_path(name, classifier, &context).unwrap_or(library_info.path);
+
             let path = libraries_path
-                .join(&library_info.path.replace("\\", MAIN_SEPARATOR_STR))
+                .join(&library_path.replace("\\", MAIN_SEPARATOR_STR))
                 .to_string_lossy()
                 .into_owned()
                 .replace("\\", MAIN_SEPARATOR_STR);
@@ -250,4 +261,5 @@ pub enum VersionBase {
     NEOFORGE,
     FABRIC,
     LITELOADER,
+    QUILT,
 }
\ No newline at end of file
@@ -0,0 +1,108 @@
+use crate::models::downloader::AssetObjects;
+use crate::models::error::AppError;
+use crate::services::directory_manager::{get_assets_directory, get_minecraft_directory};
+use crate::services::object_store::{link_from_store, place};
+use log::info;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Where the game reads the objects of an index from by their names, `None` for indexes that read them by hash.
+fn game_assets_directory(index_id: &str, index: &AssetObjects) -> Option<PathBuf> {
+    if index.map_to_resources {
+        Some(get_minecraft_directory().join("resources"))
+    } else if index.is_virtual {
+        Some(get_assets_directory().join("virtual").join(index_id))
+    } else {
+        None
+    }
+}
+
+/// The path of an object relative to the directory it's laid out in, `None` for names that would end up outside of it
+/// (e.g. absolute ones or ones going through `..`).
+fn enclosed_asset_path(name: &str) -> Option<&Path> {
+    let path = Path::new(name);
+    let is_plain = path.components().all(|x| matches!(x, Component::Normal(_)));
+    (is_plain && !name.is_empty()).then_some(path)
+}
+
+/// Records that every object of an index was laid out, holding the SHA1 of the index it was done for.
+fn layout_marker(index_id: &str) -> PathBuf {
+    get_assets_directory().join("layouts").join(format!("{index_id}.sha1"))
+}
+
+/// Lays the objects of a legacy index out by their names, in `assets/virtual/<id>` or the game's `resources`
+/// directory depending on its flags. objects are linked from the store (or `assets/objects`) so they don't take
+/// any more space, and the ones that are already in place are left alone. once a layout is complete it isn't
+/// looked at again until the index changes, unless `recheck` is set.
+///
+/// Returns the directory the objects were laid out in, `None` when the index isn't a legacy one.
+pub fn materialize_assets(index_id: &str, recheck: bool) -> Result<Option<PathBuf>, AppError> {
+    let index_path = get_assets_directory().join("indexes").join(format!("{index_id}.json"));
+    let content = match fs::read_to_string(&index_path) {
+        Ok(content) => content,
+        Err(e) => {
+            info!("Couldn't read the asset index {}: {e}", index_path.display());
+            return Ok(None);
+        }
+    };
+    let index: AssetObjects = serde_json::from_str(&content)
+        .map_err(|e| AppError::JsonParseFailed(format!("Asset index isn't well formatted: {}", e)))?;
+    let Some(directory) = game_assets_directory(index_id, &index) else {
+        return Ok(None);
+    };
+    let index_hash = Sha1::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    let marker = layout_marker(index_id);
+    if !recheck && directory.is_dir() && fs::read_to_string(&marker).map(|x| x == index_hash).unwrap_or(false) {
+        return Ok(Some(directory));
+    }
+
+    let objects = get_assets_directory().join("objects");
+    let mut missing = 0;
+    for (name, entry) in &index.objects {
+        let Some(relative) = enclosed_asset_path(name) else {
+            info!("Skipping {name} of {index_id}, it points outside of {}", directory.display());
+            continue;
+        };
+        let dest = directory.join(relative);
+        if dest.metadata().map(|x| x.len() == entry.size).unwrap_or(false) {
+            continue;
+        }
+        if link_from_store(&entry.hash, &dest)? {
+            continue;
+        }
+        let object = objects.join(entry.hash.get(0..2).unwrap_or_default()).join(&entry.hash);
+        if object.is_file() {
+            place(&object, &dest)?;
+        } else {
+            missing += 1;
+        }
+    }
+    if missing > 0 {
+        info!("{missing} objects of {index_id} aren't downloaded, they're left out of {}", directory.display());
+    } else {
+        if let Some(parent) = marker.parent() {
+            fs::create_dir_all(parent).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+        }
+        fs::write(&marker, index_hash).map_err(|e| AppError::FileWriteFailed(e.to_string()))?;
+    }
+    Ok(Some(directory))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asset_names_stay_in_their_directory() {
+        let name = "minecraft/sounds/ambient/cave/cave1.ogg";
+        assert_eq!(enclosed_asset_path(name), Some(Path::new(name)));
+        assert_eq!(enclosed_asset_path("../../.bashrc"), None);
+        assert_eq!(enclosed_asset_path("sounds/../../escape"), None);
+        assert_eq!(enclosed_asset_path("/etc/passwd"), None);
+        assert_eq!(enclosed_asset_path(""), None);
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// A token bucket capping how many bytes per second go through it, shared by every transfer it's handed to.
+///
+/// The limit can be changed at any time and applies from the next chunk on, a limit of 0 means unlimited.
+pub struct BandwidthLimiter {
+    limit: AtomicU64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    available: f64,
+    refilled_at: Instant,
+}
+
+impl BandwidthLimiter {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit: AtomicU64::new(limit),
+            bucket: Mutex::new(Bucket {
+                available: limit as f64,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    pub fn set_limit(&self, limit: u64) {
+        self.limit.store(limit, Ordering::Relaxed);
+    }
+
+    /// Takes `bytes` out of the bucket, waiting for as long as it takes to refill when there isn't enough in it.
+    pub async fn consume(&self, bytes: u64) {
+        let limit = self.limit();
+        if limit == 0 {
+            return;
+        }
+        // Waiting while holding the bucket keeps the transfers sharing it in line behind each other.
+        let mut bucket = self.bucket.lock().await;
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        // A second worth of bytes at most, so an idle period doesn't turn into a burst.
+        bucket.available = (bucket.available + elapsed * limit as f64).min(limit as f64);
+        bucket.refilled_at = now;
+        bucket.available -= bytes as f64;
+        if bucket.available < 0.0 {
+            let wait = Duration::from_secs_f64(-bucket.available / limit as f64);
+            tokio::time::sleep(wait).await;
+            bucket.available = 0.0;
+            bucket.refilled_at = Instant::now();
+        }
+    }
+}
@@ -68,13 +68,21 @@ pub async fn create_necessary_dirs() -> Void {
     create_dir_all(get_assets_directory()).await.map_err(|x| AppError::DirCreateFailed(x.to_string()))?;
     create_dir_all(get_java_dir()).await.map_err(|x| AppError::DirCreateFailed(x.to_string()))?;
     create_dir_all(get_mirrors_dir()).await.map_err(|x| AppError::DirCreateFailed(x.to_string()))?;
+    create_dir_all(get_metadata_cache_directory())
+        .await
+        .map_err(|x| AppError::DirCreateFailed(x.to_string()))?;
     mojang_mirror().write().map_err(|x| AppError::FileWriteFailed(x.to_string()))?;
 
     Ok(())
 }
 
-pub fn version_manifest_directory() -> PathBuf {
-    get_versions_directory().join("version_manifest_v2.json")
+/// The content addressed store every file with a known SHA1 is linked from, see `object_store`.
+pub fn get_object_store_directory() -> PathBuf {
+    get_falcon_launcher_directory().join("store")
+}
+
+pub fn get_metadata_cache_directory() -> PathBuf {
+    get_falcon_launcher_directory().join("cache").join("metadata")
 }
 
 pub fn get_config_directory() -> PathBuf {
@@ -129,6 +137,10 @@ pub fn get_mirrors_dir() -> PathBuf {
     get_falcon_launcher_directory().join("mirrors")
 }
 
+pub fn get_mirror_benchmarks_file() -> PathBuf {
+    get_falcon_launcher_directory().join("mirror_benchmarks.json")
+}
+
 pub fn get_version_manifest(id: &String) -> PathBuf {
     get_version_directory(id).join(format!("{}.json", id))
 }
\ No newline at end of file
@@ -0,0 +1,392 @@
+use crate::models::download_job::{DownloadJobStatus, DownloadProgress};
+use crate::models::downloader::VersionLoader;
+use crate::models::error::{AppError, Void};
+use crate::services::bandwidth_limiter::BandwidthLimiter;
+use crate::services::game_downloader::install_version;
+use crate::services::mirror_manager::apply_auto_mirror;
+use crate::services::utils::remove_if_exists;
+use crate::{AppState, GLOBAL_CACHE};
+use log::{error, info};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::spawn;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_dialog::DialogExt;
+use tokio::sync::{watch, Semaphore};
+use uuid::Uuid;
+
+/// How many installations are allowed to run at the same time, the rest wait in the queue.
+const MAX_PARALLEL_JOBS: usize = 2;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JobControl {
+    Running,
+    Paused,
+    Cancelled,
+}
+
+/// A queued or running installation. transfers made on behalf of it report their progress here and stop at
+/// the next chunk whenever the job gets paused or cancelled.
+pub struct DownloadJob {
+    pub id: String,
+    pub version_loader: VersionLoader,
+    pub name: String,
+    control: watch::Sender<JobControl>,
+    status: Mutex<DownloadJobStatus>,
+    started: AtomicBool,
+    stage: Mutex<String>,
+    current_file: Mutex<String>,
+    error: Mutex<Option<String>>,
+    bytes_done: AtomicU64,
+    bytes_total: AtomicU64,
+    files_done: AtomicU64,
+    files_total: AtomicU64,
+    speed: AtomicU64,
+    /// The job's own bandwidth cap, on top of the global one.
+    pub bandwidth: BandwidthLimiter,
+}
+
+impl DownloadJob {
+    fn new(version_loader: VersionLoader, name: String, bandwidth_limit: u64) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            version_loader,
+            name,
+            control: watch::Sender::new(JobControl::Running),
+            status: Mutex::new(DownloadJobStatus::Queued),
+            started: AtomicBool::new(false),
+            stage: Mutex::new("Queued".to_string()),
+            current_file: Mutex::new(String::new()),
+            error: Mutex::new(None),
+            bytes_done: AtomicU64::new(0),
+            bytes_total: AtomicU64::new(0),
+            files_done: AtomicU64::new(0),
+            files_total: AtomicU64::new(0),
+            speed: AtomicU64::new(0),
+            bandwidth: BandwidthLimiter::new(bandwidth_limit),
+        }
+    }
+
+    /// Waits while the job is paused and fails once it's cancelled. Called between chunks of every transfer.
+    pub async fn checkpoint(&self) -> Void {
+        let mut control = self.control.subscribe();
+        loop {
+            let current = *control.borrow_and_update();
+            match current {
+                JobControl::Running => return Ok(()),
+                JobControl::Cancelled => return Err(AppError::DownloadCancelled(self.id.clone())),
+                JobControl::Paused => {
+                    control
+                        .changed()
+                        .await
+                        .map_err(|e| AppError::Internal(e.to_string()))?;
+                }
+            }
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        *self.control.borrow() == JobControl::Running
+    }
+
+    pub fn status(&self) -> DownloadJobStatus {
+        self.status
+            .lock()
+            .map(|x| *x)
+            .unwrap_or(DownloadJobStatus::Failed)
+    }
+
+    fn set_status(&self, status: DownloadJobStatus) {
+        if let Ok(mut guard) = self.status.lock() {
+            *guard = status;
+        }
+    }
+
+    pub fn set_stage(&self, stage: &str) {
+        if let Ok(mut guard) = self.stage.lock() {
+            *guard = stage.to_string();
+        }
+    }
+
+    pub fn set_current_file(&self, file: &str) {
+        if let Ok(mut guard) = self.current_file.lock() {
+            *guard = file.to_string();
+        }
+    }
+
+    /// Registers a batch of files that are about to be downloaded.
+    pub fn add_expected(&self, bytes: u64, files: u64) {
+        self.bytes_total.fetch_add(bytes, Ordering::Relaxed);
+        self.files_total.fetch_add(files, Ordering::Relaxed);
+    }
+
+    pub fn add_bytes(&self, bytes: u64) {
+        self.bytes_done.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Takes back the bytes of a download attempt that got thrown away.
+    pub fn remove_bytes(&self, bytes: u64) {
+        let _ = self
+            .bytes_done
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| Some(x.saturating_sub(bytes)));
+    }
+
+    pub fn file_done(&self) {
+        self.files_done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn progress(&self) -> DownloadProgress {
+        let bytes_done = self.bytes_done.load(Ordering::Relaxed);
+        let bytes_total = self.bytes_total.load(Ordering::Relaxed).max(bytes_done);
+        let speed = self.speed.load(Ordering::Relaxed);
+        let eta = if speed > 0 {
+            Some((bytes_total - bytes_done) / speed)
+        } else {
+            None
+        };
+        DownloadProgress {
+            task_id: self.id.clone(),
+            version: self.version_loader.id.clone(),
+            name: self.name.clone(),
+            status: self.status(),
+            stage: self.stage.lock().map(|x| x.clone()).unwrap_or_default(),
+            current_file: self.current_file.lock().map(|x| x.clone()).unwrap_or_default(),
+            bytes_done,
+            bytes_total,
+            files_done: self.files_done.load(Ordering::Relaxed),
+            files_total: self.files_total.load(Ordering::Relaxed),
+            speed,
+            eta,
+            bandwidth_limit: self.bandwidth.limit(),
+            error: self.error.lock().ok().and_then(|x| x.clone()),
+        }
+    }
+
+    fn pause(&self) {
+        if self.status().is_finished() {
+            return;
+        }
+        self.control.send_replace(JobControl::Paused);
+        self.set_status(DownloadJobStatus::Paused);
+    }
+
+    fn resume(&self) {
+        if self.status() != DownloadJobStatus::Paused {
+            return;
+        }
+        self.control.send_replace(JobControl::Running);
+        if self.started.load(Ordering::Relaxed) {
+            self.set_status(DownloadJobStatus::Running);
+        } else {
+            self.set_status(DownloadJobStatus::Queued);
+        }
+    }
+
+    fn cancel(&self) {
+        if self.status().is_finished() {
+            return;
+        }
+        self.control.send_replace(JobControl::Cancelled);
+        self.set_status(DownloadJobStatus::Cancelled);
+    }
+
+    fn finish(&self, result: &Result<(), AppError>) {
+        match result {
+            Ok(()) => {
+                self.set_stage("Done");
+                self.set_status(DownloadJobStatus::Completed);
+            }
+            Err(AppError::DownloadCancelled(_)) => self.set_status(DownloadJobStatus::Cancelled),
+            Err(e) => {
+                if let Ok(mut guard) = self.error.lock() {
+                    *guard = Some(e.to_string());
+                }
+                self.set_status(DownloadJobStatus::Failed);
+            }
+        }
+        self.speed.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Keeps track of every install job of this session and runs them in the background.
+pub struct DownloadManager {
+    jobs: Mutex<Vec<Arc<DownloadJob>>>,
+    slots: Arc<Semaphore>,
+}
+
+impl DownloadManager {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(Vec::new()),
+            slots: Arc::new(Semaphore::new(MAX_PARALLEL_JOBS)),
+        }
+    }
+
+    /// Queues the installation of the given version and returns its task id right away.
+    pub fn enqueue(
+        &self,
+        app_handle: AppHandle,
+        version_loader: VersionLoader,
+        name: String,
+        bandwidth_limit: u64,
+    ) -> Result<String, AppError> {
+        let job = Arc::new(DownloadJob::new(version_loader, name, bandwidth_limit));
+        self.jobs
+            .lock()
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .push(job.clone());
+        info!("Queued the installation of {} as {}", job.version_loader.id, job.id);
+
+        let slots = self.slots.clone();
+        let id = job.id.clone();
+        spawn(async move {
+            spawn(report_progress(app_handle.clone(), job.clone()));
+            let result = run_job(&app_handle, &job, slots).await;
+            if let Err(e) = &result {
+                error!("Installation of {} has stopped: {e}", job.version_loader.id);
+            }
+            job.finish(&result);
+            emit_progress(&app_handle, &job);
+        });
+        Ok(id)
+    }
+
+    pub fn list(&self) -> Result<Vec<DownloadProgress>, AppError> {
+        Ok(self
+            .jobs
+            .lock()
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .iter()
+            .map(|x| x.progress())
+            .collect())
+    }
+
+    pub fn get(&self, task_id: &str) -> Result<Arc<DownloadJob>, AppError> {
+        self.jobs
+            .lock()
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .iter()
+            .find(|x| x.id == task_id)
+            .cloned()
+            .ok_or_else(|| AppError::DownloadJobNotFound(task_id.to_string()))
+    }
+
+    pub fn pause(&self, task_id: &str) -> Void {
+        self.get(task_id)?.pause();
+        Ok(())
+    }
+
+    pub fn resume(&self, task_id: &str) -> Void {
+        self.get(task_id)?.resume();
+        Ok(())
+    }
+
+    pub fn cancel(&self, task_id: &str) -> Void {
+        self.get(task_id)?.cancel();
+        Ok(())
+    }
+
+    /// Changes the bandwidth cap of the job, the transfers it's running follow it from their next chunk on.
+    pub fn set_bandwidth_limit(&self, task_id: &str, limit: u64) -> Void {
+        self.get(task_id)?.bandwidth.set_limit(limit);
+        Ok(())
+    }
+
+    /// Whether any job is still queued, running or paused.
+    pub fn has_unfinished(&self) -> Result<bool, AppError> {
+        Ok(self
+            .jobs
+            .lock()
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .iter()
+            .any(|x| !x.status().is_finished()))
+    }
+
+    /// Forgets about the jobs that are already finished.
+    pub fn clear_finished(&self) -> Void {
+        self.jobs
+            .lock()
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .retain(|x| !x.status().is_finished());
+        Ok(())
+    }
+}
+
+async fn run_job(app_handle: &AppHandle, job: &Arc<DownloadJob>, slots: Arc<Semaphore>) -> Void {
+    // Waiting for a slot only once the job is allowed to run, a job paused in the queue would hold it otherwise.
+    let _slot = loop {
+        job.checkpoint().await?;
+        let slot = slots
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        if job.is_running() {
+            break slot;
+        }
+    };
+    job.started.store(true, Ordering::Relaxed);
+    job.set_status(DownloadJobStatus::Running);
+
+    let state = app_handle.state::<AppState>();
+    let client = state.http.with_job(job.clone());
+    let mut settings = state.config.read().await.download_settings.clone();
+    apply_auto_mirror(&client, &mut settings).await?;
+    let result = install_version(
+        &client,
+        &job.version_loader,
+        &job.name,
+        app_handle,
+        &state.log_tx,
+        &settings,
+    )
+    .await;
+    remove_if_exists(&client.temp_directory())?;
+    let version = result?;
+
+    app_handle
+        .dialog()
+        .message("Successfully installed the selected version you can now play it")
+        .title("Done!")
+        .show(|_| {});
+    let mut global = GLOBAL_CACHE.lock().await;
+    global.versions.push(version);
+    Ok(())
+}
+
+/// Emits the job's progress every now and then until it's finished, measuring its download speed on the way.
+async fn report_progress(app_handle: AppHandle, job: Arc<DownloadJob>) {
+    let mut last_bytes = 0;
+    let mut last_tick = Instant::now();
+    while !job.status().is_finished() {
+        tokio::time::sleep(PROGRESS_INTERVAL).await;
+        let bytes = job.bytes_done.load(Ordering::Relaxed);
+        let elapsed = last_tick.elapsed().as_secs_f64();
+        if elapsed > 0.0 && job.status() == DownloadJobStatus::Running {
+            let current = bytes.saturating_sub(last_bytes) as f64 / elapsed;
+            let previous = job.speed.load(Ordering::Relaxed) as f64;
+            // Smoothing it out so the ETA doesn't jump around on every tick.
+            let speed = if previous == 0.0 {
+                current
+            } else {
+                previous * 0.7 + current * 0.3
+            };
+            job.speed.store(speed as u64, Ordering::Relaxed);
+        } else {
+            job.speed.store(0, Ordering::Relaxed);
+        }
+        last_bytes = bytes;
+        last_tick = Instant::now();
+        if !job.status().is_finished() {
+            emit_progress(&app_handle, &job);
+        }
+    }
+}
+
+fn emit_progress(app_handle: &AppHandle, job: &DownloadJob) {
+    app_handle
+        .emit("download-progress", job.progress())
+        .unwrap_or_else(|x| info!("Failed to emit progress to download-progress event. detailed error: \n {x}"));
+}
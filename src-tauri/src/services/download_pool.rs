@@ -0,0 +1,121 @@
+use crate::models::config::DownloadSettings;
+use crate::models::downloader::DownloadTask;
+use crate::models::error::{AppError, Void};
+use crate::services::game_downloader::download_file_if_not_exists;
+use crate::services::http_client::HttpClient;
+use crate::services::utils::update_download_bar;
+use log::info;
+use std::future::Future;
+use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Downloads every given task through a shared pool of workers, keeping at most `max_concurrent_downloads`
+/// transfers in flight.
+///
+/// Each task still goes through `download_file_if_not_exists`, so files that already match their SHA1/size are skipped
+/// and the rest fail over through the configured mirrors.
+/// Progress is reported on the progress bar (if an app handle is given) based on the bytes of the finished tasks,
+/// and on the download job the client is attached to, if any. the first failure cancels the remaining tasks and gets returned.
+pub async fn download_all(
+    client: &HttpClient,
+    tasks: Vec<DownloadTask>,
+    settings: &DownloadSettings,
+    app_handle: Option<&AppHandle>,
+) -> Void {
+    if tasks.is_empty() {
+        return Ok(());
+    }
+    let concurrency = settings.concurrency();
+    let mirrors = Arc::new(settings.mirrors.clone());
+    let total_files = tasks.len() as u64;
+    let total_bytes = tasks.iter().map(|x| x.size).sum::<u64>();
+    info!("Downloading {total_files} files ({total_bytes} bytes) with {concurrency} workers.");
+    if let Some(job) = client.job() {
+        job.add_expected(total_bytes, total_files);
+    }
+
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut workers = JoinSet::new();
+    for task in tasks {
+        let client = client.clone();
+        let mirrors = mirrors.clone();
+        spawn_bounded(&mut workers, &semaphore, async move {
+            download_file_if_not_exists(&client, &task.path, task.url.clone(), &task.sha1, task.size, &mirrors)
+                .await?;
+            Ok(task.size)
+        });
+    }
+
+    let mut finished_files = 0;
+    let mut finished_bytes = 0;
+    while let Some(result) = workers.join_next().await {
+        let size = result.map_err(|e| AppError::Internal(format!("Download worker failed: {e}")))??;
+        finished_files += 1;
+        finished_bytes += size;
+        if let Some(job) = client.job() {
+            job.file_done();
+        }
+        if let Some(app_handle) = app_handle {
+            let progress = if total_bytes > 0 {
+                finished_bytes * 100 / total_bytes
+            } else {
+                finished_files * 100 / total_files
+            };
+            update_download_bar(progress as i64, app_handle);
+        }
+    }
+    Ok(())
+}
+
+/// Spawns `future` into `workers` behind one of the semaphore's permits, so no more futures run at once than it has permits.
+fn spawn_bounded<T, F>(workers: &mut JoinSet<Result<T, AppError>>, semaphore: &Arc<Semaphore>, future: F)
+where
+    T: Send + 'static,
+    F: Future<Output = Result<T, AppError>> + Send + 'static,
+{
+    let semaphore = semaphore.clone();
+    workers.spawn(async move {
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        future.await
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn workers_never_outnumber_the_permits() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let semaphore = Arc::new(Semaphore::new(3));
+            let in_flight = Arc::new(AtomicUsize::new(0));
+            let peak = Arc::new(AtomicUsize::new(0));
+            let mut workers = JoinSet::new();
+            for _ in 0..20 {
+                let in_flight = in_flight.clone();
+                let peak = peak.clone();
+                spawn_bounded(&mut workers, &semaphore, async move {
+                    peak.fetch_max(in_flight.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    Ok(())
+                });
+            }
+            while let Some(result) = workers.join_next().await {
+                result.unwrap().unwrap();
+            }
+            assert_eq!(peak.load(Ordering::SeqCst), 3);
+        });
+    }
+}
@@ -0,0 +1,399 @@
+use crate::models::config::DownloadSettings;
+use crate::models::downloader::{ForgeInstallProfileV1, ForgeLibrary, ForgeProcessor};
+use crate::models::error::{AppError, Void};
+use crate::models::mirror::Mirror;
+use crate::models::platform::get_current_os;
+use crate::models::versions::MinecraftVersion;
+use crate::services::directory_manager::{
+    get_libraries_directory, get_minecraft_directory, get_version_directory, get_version_manifest,
+};
+use crate::services::game_downloader::{download_file_if_not_exists, report_stage};
+use crate::services::http_client::HttpClient;
+use crate::services::jdk_manager::get_java;
+use crate::services::utils::{convert_to_full_url, patch_java_permission_linux, verify_file_existence_with_sha};
+use log::info;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::fs;
+use std::fs::{create_dir_all, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::AppHandle;
+use zip::ZipArchive;
+
+/// Installs a modern (1.13+) Forge or a NeoForge version the way its installer would, without running the
+/// installer: the version JSON and libraries are taken from the jar. the processors patch the vanilla jar, so they
+/// are left to [`run_processors`] once the caller downloaded the version. returns the id of the installed version.
+pub async fn install_forge(
+    client: &HttpClient,
+    installer: &Path,
+    app_handle: &AppHandle,
+    settings: &DownloadSettings,
+) -> Result<String, AppError> {
+    let mut zip = open_installer(installer)?;
+    let profile: ForgeInstallProfileV1 = read_json_entry(&mut zip, "install_profile.json")?;
+    info!("Installing {} (install profile spec {})", profile.version, profile.spec.unwrap_or(0));
+
+    report_stage(client, &format!("Installing {}...", profile.version), app_handle);
+    let version_json: serde_json::Value = read_json_entry(&mut zip, profile.json.trim_start_matches('/'))?;
+    create_dir_all(get_version_directory(&profile.version)).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    let content = serde_json::to_string(&version_json).map_err(|e| AppError::JsonParseFailed(e.to_string()))?;
+    fs::write(get_version_manifest(&profile.version), content)
+        .map_err(|e| AppError::FileWriteFailed(format!("Failed to write {}.json: {e}", profile.version)))?;
+
+    let mut libraries = profile.libraries.clone();
+    if let Some(version_libraries) = version_json.get("libraries") {
+        let version_libraries: Vec<ForgeLibrary> = serde_json::from_value(version_libraries.clone())
+            .map_err(|e| AppError::JsonParseFailed(format!("The installer's version JSON isn't well formatted: {e}")))?;
+        libraries.extend(version_libraries);
+    }
+    for library in &libraries {
+        install_library(client, &mut zip, library, &settings.mirrors).await?;
+    }
+    Ok(profile.version)
+}
+
+/// Runs every client processor of the installer and verifies its outputs, then records the files they produced
+/// next to the version. the vanilla version and its Java runtime have to be downloaded already.
+pub async fn run_processors(client: &HttpClient, installer: &Path, app_handle: &AppHandle) -> Void {
+    let mut zip = open_installer(installer)?;
+    let profile: ForgeInstallProfileV1 = read_json_entry(&mut zip, "install_profile.json")?;
+    let temp_dir = client.temp_directory();
+    let data = resolve_data(&profile, &mut zip, installer, &temp_dir)?;
+    let java_component = MinecraftVersion::from_id(profile.minecraft.clone())
+        .load_json()
+        .pointer("/javaVersion/component")
+        .and_then(|x| x.as_str())
+        .unwrap_or("jre-legacy")
+        .to_string();
+    let java = get_java(java_component)?;
+    patch_java_permission_linux(&java)?;
+
+    let processors = profile
+        .processors
+        .iter()
+        .filter(|x| x.sides.as_ref().map(|sides| sides.iter().any(|x| x == "client")).unwrap_or(true))
+        .collect::<Vec<_>>();
+    for (i, processor) in processors.iter().enumerate() {
+        if let Some(job) = client.job() {
+            job.checkpoint().await?;
+        }
+        report_stage(client, &format!("Installing {}... ({}/{})", profile.version, i + 1, processors.len()), app_handle);
+        run_processor(&java.get_bin_file(), processor, &data, &temp_dir).await?;
+    }
+    record_outputs(&profile, &processors, &data)
+}
+
+/// The file listing what the processors of the given version produced, relative to the libraries directory. no
+/// version JSON refers to those files, this is how the garbage collector knows they're in use.
+pub fn processor_outputs_file(version_id: &String) -> PathBuf {
+    get_version_directory(version_id).join("processor-outputs.txt")
+}
+
+/// The libraries the profile's data points at (e.g. the srg and extra jars) and the declared processor outputs.
+fn record_outputs(profile: &ForgeInstallProfileV1, processors: &[&ForgeProcessor], data: &HashMap<String, String>) -> Void {
+    let libraries_dir = get_libraries_directory();
+    let mut outputs = Vec::new();
+    for value in profile.data.values() {
+        if let Some(coordinates) = value.client.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+            outputs.push(maven_path(coordinates)?);
+        }
+    }
+    for processor in processors {
+        for file in processor.outputs.keys() {
+            let file = PathBuf::from(resolve_arg(file, data)?);
+            if let Ok(relative) = file.strip_prefix(&libraries_dir) {
+                outputs.push(relative.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    outputs.sort();
+    outputs.dedup();
+    fs::write(processor_outputs_file(&profile.version), outputs.join("\n"))
+        .map_err(|e| AppError::FileWriteFailed(format!("Failed to record the outputs of {}: {e}", profile.version)))
+}
+
+/// Libraries without a url are shipped in the installer's `maven` directory, or produced by the processors when
+/// they aren't in there either.
+async fn install_library(
+    client: &HttpClient,
+    zip: &mut ZipArchive<File>,
+    library: &ForgeLibrary,
+    mirrors: &[Mirror],
+) -> Void {
+    let artifact = library.downloads.as_ref().and_then(|x| x.artifact.as_ref());
+    let path = match artifact.and_then(|x| x.path.clone()) {
+        Some(path) => path,
+        None => maven_path(&library.name)?,
+    };
+    let full_path = get_libraries_directory().join(&path);
+    let url = artifact.map(|x| x.url.clone()).or_else(|| library.url.clone()).unwrap_or_default();
+
+    if url.is_empty() {
+        let name = format!("maven/{path}");
+        if zip.by_name(&name).is_ok() {
+            extract_entry(zip, &name, &full_path)?;
+        }
+        return Ok(());
+    }
+    let full_url = if url.ends_with('/') {
+        convert_to_full_url(url, library.name.clone())?
+    } else {
+        url
+    };
+    let hash = artifact.and_then(|x| x.sha1.clone()).unwrap_or_default();
+    let size = artifact.and_then(|x| x.size).unwrap_or(0);
+    download_file_if_not_exists(client, &full_path, full_url, &hash, size, mirrors).await
+}
+
+/// The client side of the profile's `data`, alongside the entries the installer always provides. files of the
+/// installer that are referred to get extracted so the processors can read them.
+fn resolve_data(
+    profile: &ForgeInstallProfileV1,
+    zip: &mut ZipArchive<File>,
+    installer: &Path,
+    temp_dir: &Path,
+) -> Result<HashMap<String, String>, AppError> {
+    let minecraft_jar = get_version_directory(&profile.minecraft).join(format!("{}.jar", profile.minecraft));
+    let mut data = HashMap::from([
+        ("SIDE".to_string(), "client".to_string()),
+        ("MINECRAFT_JAR".to_string(), minecraft_jar.display().to_string()),
+        ("MINECRAFT_VERSION".to_string(), profile.minecraft.clone()),
+        ("ROOT".to_string(), get_minecraft_directory().display().to_string()),
+        ("INSTALLER".to_string(), installer.display().to_string()),
+        ("LIBRARY_DIR".to_string(), get_libraries_directory().display().to_string()),
+    ]);
+
+    let extracted = temp_dir.join("forge");
+    for (key, value) in &profile.data {
+        let value = &value.client;
+        let resolved = if let Some(coordinates) = value.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+            get_libraries_directory().join(maven_path(coordinates)?).display().to_string()
+        } else if let Some(literal) = value.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')) {
+            literal.to_string()
+        } else if let Some(name) = value.strip_prefix('/') {
+            let dest = extracted.join(name);
+            extract_entry(zip, name, &dest)?;
+            dest.display().to_string()
+        } else {
+            value.clone()
+        };
+        data.insert(key.clone(), resolved);
+    }
+    Ok(data)
+}
+
+async fn run_processor(
+    java: &Path,
+    processor: &ForgeProcessor,
+    data: &HashMap<String, String>,
+    temp_dir: &Path,
+) -> Void {
+    if !processor.outputs.is_empty() && outputs_match(processor, data)? {
+        info!("Outputs of {} are already in place, skipping it", processor.jar);
+        return Ok(());
+    }
+
+    let jar = get_libraries_directory().join(maven_path(&processor.jar)?);
+    let main_class = read_main_class(&jar)?;
+    let mut classpath = vec![jar.display().to_string()];
+    for library in &processor.classpath {
+        classpath.push(get_libraries_directory().join(maven_path(library)?).display().to_string());
+    }
+    let args = processor
+        .args
+        .iter()
+        .map(|x| resolve_arg(x, data))
+        .collect::<Result<Vec<_>, _>>()?;
+    let separator = if get_current_os() == "windows" { ";" } else { ":" };
+
+    info!("Running {} ({main_class})", processor.jar);
+    let mut command = Command::new(java);
+    command
+        .arg("-cp")
+        .arg(classpath.join(separator))
+        .arg(&main_class)
+        .args(&args)
+        .current_dir(temp_dir);
+    let output = tauri::async_runtime::spawn_blocking(move || command.output())
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .map_err(|e| AppError::ForgeProcessorFailed(format!("Failed to start {}: {e}", processor.jar)))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in stdout.lines() {
+        info!("[{}][stdout] {line}", processor.jar);
+    }
+    for line in stderr.lines() {
+        info!("[{}][stderr] {line}", processor.jar);
+    }
+    if !output.status.success() {
+        let reason = stderr.lines().last().unwrap_or_default();
+        return Err(AppError::ForgeProcessorFailed(format!("{} exited with {}: {reason}", processor.jar, output.status)));
+    }
+    if !outputs_match(processor, data)? {
+        return Err(AppError::ForgeProcessorFailed(format!(
+            "{} didn't produce the files it declares",
+            processor.jar
+        )));
+    }
+    Ok(())
+}
+
+fn outputs_match(processor: &ForgeProcessor, data: &HashMap<String, String>) -> Result<bool, AppError> {
+    for (file, sha1) in &processor.outputs {
+        let file = resolve_arg(file, data)?;
+        let sha1 = resolve_arg(sha1, data)?;
+        if !verify_file_existence_with_sha(&file, &sha1)? {
+            info!("{file} doesn't match {sha1}");
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Arguments are either maven coordinates in brackets, a quoted literal, or text whose `{KEY}`s refer to the data.
+fn resolve_arg(arg: &str, data: &HashMap<String, String>) -> Result<String, AppError> {
+    if let Some(coordinates) = arg.strip_prefix('[').and_then(|x| x.strip_suffix(']')) {
+        return Ok(get_libraries_directory().join(maven_path(coordinates)?).display().to_string());
+    }
+    if let Some(literal) = arg.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')) {
+        return Ok(literal.to_string());
+    }
+    let mut resolved = String::new();
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let key = &rest[start + 1..start + end];
+        let value = data
+            .get(key)
+            .ok_or_else(|| AppError::ForgeProcessorFailed(format!("{arg} refers to the missing data {key}")))?;
+        resolved.push_str(&rest[..start]);
+        resolved.push_str(value);
+        rest = &rest[start + end + 1..];
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+/// The path of an artifact in a maven repository, e.g. `group:artifact:version:classifier@ext` becomes
+/// `group/artifact/version/artifact-version-classifier.ext`.
+fn maven_path(coordinates: &str) -> Result<String, AppError> {
+    let (coordinates, extension) = coordinates.split_once('@').unwrap_or((coordinates, "jar"));
+    let args = coordinates.split(':').collect::<Vec<&str>>();
+    if args.len() < 3 {
+        return Err(AppError::InvalidPath(format!("Invalid maven coordinates: {coordinates}")));
+    }
+    let group_id = args[0].replace('.', "/");
+    let (artifact, version) = (args[1], args[2]);
+    let file = match args.get(3) {
+        Some(classifier) => format!("{artifact}-{version}-{classifier}.{extension}"),
+        None => format!("{artifact}-{version}.{extension}"),
+    };
+    Ok(format!("{group_id}/{artifact}/{version}/{file}"))
+}
+
+fn read_main_class(jar: &Path) -> Result<String, AppError> {
+    let file = File::open(jar).map_err(|e| AppError::FileReadFailed(format!("{}: {e}", jar.display())))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+    let mut manifest = String::new();
+    zip.by_name("META-INF/MANIFEST.MF")
+        .map_err(|e| AppError::ZipParseFailed(format!("{} has no manifest: {e}", jar.display())))?
+        .read_to_string(&mut manifest)
+        .map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+    manifest
+        .lines()
+        .find_map(|x| x.strip_prefix("Main-Class:"))
+        .map(|x| x.trim().to_string())
+        .ok_or_else(|| AppError::ForgeProcessorFailed(format!("{} doesn't name its main class", jar.display())))
+}
+
+fn open_installer(installer: &Path) -> Result<ZipArchive<File>, AppError> {
+    let file = File::open(installer).map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+    ZipArchive::new(file).map_err(|e| AppError::ZipParseFailed(e.to_string()))
+}
+
+fn read_json_entry<T: DeserializeOwned>(zip: &mut ZipArchive<File>, name: &str) -> Result<T, AppError> {
+    let entry = zip
+        .by_name(name)
+        .map_err(|e| AppError::ZipParseFailed(format!("The installer has no {name}: {e}")))?;
+    serde_json::from_reader(entry).map_err(|e| AppError::JsonParseFailed(format!("{name} isn't well formatted: {e}")))
+}
+
+fn extract_entry(zip: &mut ZipArchive<File>, name: &str, dest: &PathBuf) -> Void {
+    let mut entry = zip
+        .by_name(name)
+        .map_err(|e| AppError::ZipParseFailed(format!("The installer has no {name}: {e}")))?;
+    if let Some(parent) = dest.parent() {
+        create_dir_all(parent).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    }
+    let mut file = File::create(dest).map_err(|e| AppError::FileCreateFailed(e.to_string()))?;
+    std::io::copy(&mut entry, &mut file).map_err(|e| AppError::ZipExtractionFailed(format!("{name}: {e}")))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maven_path_of_plain_coordinates() {
+        assert_eq!(
+            maven_path("net.minecraftforge:forge:1.20.1-47.2.0").unwrap(),
+            "net/minecraftforge/forge/1.20.1-47.2.0/forge-1.20.1-47.2.0.jar"
+        );
+    }
+
+    #[test]
+    fn maven_path_with_classifier_and_extension() {
+        assert_eq!(
+            maven_path("net.minecraft:client:1.20.1-20230612.114412:srg").unwrap(),
+            "net/minecraft/client/1.20.1-20230612.114412/client-1.20.1-20230612.114412-srg.jar"
+        );
+        assert_eq!(
+            maven_path("de.oceanlabs.mcp:mcp_config:1.20.1-20230612.114412:mappings@txt").unwrap(),
+            "de/oceanlabs/mcp/mcp_config/1.20.1-20230612.114412/mcp_config-1.20.1-20230612.114412-mappings.txt"
+        );
+        assert_eq!(
+            maven_path("de.oceanlabs.mcp:mcp_config:1.20.1@zip").unwrap(),
+            "de/oceanlabs/mcp/mcp_config/1.20.1/mcp_config-1.20.1.zip"
+        );
+    }
+
+    #[test]
+    fn maven_path_rejects_partial_coordinates() {
+        assert!(maven_path("net.minecraftforge:forge").is_err());
+    }
+
+    #[test]
+    fn resolve_arg_substitutes_data() {
+        let data = HashMap::from([
+            ("SIDE".to_string(), "client".to_string()),
+            ("MINECRAFT_JAR".to_string(), "/game/versions/1.20.1/1.20.1.jar".to_string()),
+        ]);
+        assert_eq!(resolve_arg("{MINECRAFT_JAR}", &data).unwrap(), "/game/versions/1.20.1/1.20.1.jar");
+        assert_eq!(resolve_arg("--side={SIDE}", &data).unwrap(), "--side=client");
+        assert_eq!(resolve_arg("--task", &data).unwrap(), "--task");
+    }
+
+    #[test]
+    fn resolve_arg_takes_literals_as_is() {
+        assert_eq!(resolve_arg("'{SIDE}'", &HashMap::new()).unwrap(), "{SIDE}");
+    }
+
+    #[test]
+    fn resolve_arg_resolves_coordinates_into_the_libraries() {
+        let resolved = resolve_arg("[net.minecraftforge:installertools:1.3.0]", &HashMap::new()).unwrap();
+        let expected = get_libraries_directory().join("net/minecraftforge/installertools/1.3.0/installertools-1.3.0.jar");
+        assert_eq!(PathBuf::from(resolved), expected);
+    }
+
+    #[test]
+    fn resolve_arg_fails_on_missing_data() {
+        assert!(resolve_arg("{BINPATCH}", &HashMap::new()).is_err());
+    }
+}
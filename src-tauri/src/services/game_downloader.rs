@@ -1,197 +1,305 @@
 #![allow(deprecated)]
 
-use crate::models::versions::MinecraftVersion;
+use crate::models::versions::{MinecraftVersion, VersionBase};
 use crate::services::directory_manager::{
-    get_assets_directory, get_falcon_launcher_directory, get_libraries_directory,
-    get_minecraft_directory, get_natives_directory, get_temp_directory, get_version_directory,
-    get_version_manifest, get_versions_directory,
+    get_libraries_directory, get_minecraft_directory,
+    get_natives_directory, get_version_directory, get_version_manifest,
 };
 use crate::services::utils::{
-    convert_to_full_path, convert_to_full_url, fetch_library_path, fetch_rules,
-    fetch_unofficial_library_repos, is_legacy, verify_file_existence_with_sha,
+    convert_to_full_path, convert_to_full_url, is_legacy, remove_if_exists, verify_file_existence_with_sha,
     verify_file_existence_with_size,
 };
-use crate::services::utils::{update_download, update_download_bar, update_download_status};
-use crate::services::version_manager::{load_version_manifest, load_version_manifest_local};
+use crate::services::utils::{update_download, update_download_status};
+use crate::services::version_manager::load_version_manifest;
 
-use crate::models::config::Config;
+use crate::models::config::DownloadSettings;
 use crate::models::downloader::{
-    AssetIndex, AssetObjects, DownloadDetail, ForgeInstallProfile, ForgeVersionJsonInfo, Library,
-    LibraryArtifact, Manifest, MinecraftManifestVersion, VersionLoader,
+    ForgeInstallProfile, ForgeVersionJsonInfo, Manifest, MinecraftManifestVersion, VersionLoader,
 };
 use crate::models::error::AppError;
 use crate::models::fabric::{FabricInstaller, FabricLoader, FabricMinecraftVersion};
+use crate::models::neoforge::NeoForgeVersions;
+use crate::models::quilt::{QuiltLoader, QuiltMinecraftVersion};
+use crate::models::install_plan::{InstallPlan, PlannedFile, PlannedFileKind};
 use crate::models::logger::LogLine;
 use crate::models::mirror::Mirror;
-use crate::models::platform::get_current_os;
-use crate::models::utils::{LowerCaseStartsWith, ParseWithMirror};
-use crate::services::jdk_manager::{download_java, get_java};
+use crate::services::asset_layout::materialize_assets;
+use crate::services::download_pool::download_all;
+use crate::services::forge_installer::{install_forge, run_processors};
+use crate::services::liteloader::install_liteloader;
+use crate::services::install_planner::plan_version;
+use crate::services::metadata_cache::get_cached_json;
+use crate::services::object_store::{add_to_store, extract_into_store, link_from_store};
+use crate::services::mirror_manager::{candidate_urls, get_json_from_mirrors, get_text_from_mirrors, should_fail_over};
+use crate::services::jdk_manager::{
+    download_java, get_java, install_runtime_archive, mark_runtime_executables, write_java_release,
+};
 use crate::GLOBAL_CACHE;
 use log::info;
-use reqwest::Client;
+use crate::services::http_client::{check_status, classify_error, HttpClient};
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
+use sha1::{Digest, Sha1};
 use std::collections::HashMap;
 use std::fs;
-use std::fs::{create_dir_all, exists, set_permissions, File};
-use std::io::{BufRead, BufReader, Write};
+use std::fs::{create_dir_all, exists, File};
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 use std::process::{Child, ChildStderr, Command, Stdio};
-use std::time::Duration;
 use tauri::async_runtime::block_on;
 use tauri::AppHandle;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use std::sync::{Arc, LazyLock};
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::OwnedMutexGuard;
 use zip::ZipArchive;
-use zip_extract::extract;
 
-pub async fn download_version(
-    version: &MinecraftVersion,
+/// Installs the given loader's version alongside everything it needs to launch, and returns the installed version.
+pub async fn install_version(
+    client: &HttpClient,
+    version_loader: &VersionLoader,
     name: &String,
     app_handle: &AppHandle,
     logger: &UnboundedSender<LogLine>,
-    cfg: &Config,
-) -> Result<(), AppError> {
-    let id = &version.id;
-    let mirror = &cfg.download_settings.mirror;
-    let name = if name == "" { &version.id } else { name };
+    settings: &DownloadSettings,
+) -> Result<MinecraftVersion, AppError> {
+    let mut version_id = version_loader.get_installed_id();
+    // Forge installers whose processors are left to run once the vanilla version is downloaded.
+    let mut pending_installer = None;
+    let mirrors = &settings.mirrors;
+    info!(
+        "DEBUG: Downloading version {} from {} mirrors",
+        version_loader.id,
+        mirrors.iter().map(|x| x.name.as_str()).collect::<Vec<&str>>().join(", ")
+    );
+    if version_loader.base == VersionBase::FORGE {
+        info!(
+            "DEBUG: Forge version detected! {} installing it rn!",
+            version_loader.id
+        );
+        report_stage(client, "Installing Forge...", app_handle);
+        pending_installer = download_forge_version(
+            client,
+            &version_loader.id,
+            app_handle,
+            logger,
+            settings,
+            &mut version_id,
+        )
+        .await?;
+    };
+    if version_loader.base == VersionBase::NEOFORGE {
+        info!("NeoForge version detected! {} installing it", version_loader.id);
+        report_stage(client, "Installing NeoForge...", app_handle);
+        pending_installer = download_neoforge_version(
+            client,
+            &version_loader.id,
+            app_handle,
+            settings,
+            &mut version_id,
+        )
+        .await?;
+    }
+    if version_loader.base == VersionBase::FABRIC {
+        info!(
+            "DEBUG: Fabric version detected! {} installing it rn!",
+            version_loader.id
+        );
+        report_stage(client, "Installing Fabric...", app_handle);
+        download_fabric(client, version_loader, logger, settings).await?;
+    }
+    if version_loader.base == VersionBase::QUILT {
+        info!("Quilt version detected! {} installing it", version_loader.id);
+        report_stage(client, "Installing Quilt...", app_handle);
+        download_quilt(client, version_loader, settings).await?;
+    }
+    if version_loader.base == VersionBase::LITELOADER {
+        info!("LiteLoader version detected! {} installing it", version_loader.id);
+        report_stage(client, "Installing LiteLoader...", app_handle);
+        install_liteloader(client, version_loader, settings).await?;
+    }
 
-    info!("Downloading version {} with name of {name}", &version.id);
-    let manifest = load_version_manifest(&mirror).await?;
+    info!("Downloading {version_id}.json");
 
-    let content = fs::read_to_string(PathBuf::from(version.get_json()))
-        .map_err(|x| AppError::FileReadFailed(format!("Failed to read version JSON: {}", x)))?;
-    let json: MinecraftManifestVersion =
-        serde_json::from_str(&content).map_err(|x| AppError::JsonParseFailed(x.to_string()))?;
+    let manifest = load_version_manifest(client, settings).await?;
+    if version_loader.base == VersionBase::VANILLA {
+        download_from_manifest(client, &version_id, &manifest, mirrors).await?;
+    }
+    let version = MinecraftVersion::from_id(version_id);
 
-    let java_version = if let Some(jv) = &json.java_version {
-        jv.clone()
-    } else if let Some(inherits) = &json.inherits_from {
-        let dir = get_version_manifest(inherits);
-        let content = fs::read_to_string(dir)
-            .map_err(|x| AppError::FileReadFailed(format!("Failed to read inherited JSON: {}", x)))?;
-        let m: MinecraftManifestVersion = serde_json::from_str(&content)
-            .map_err(|x| AppError::JsonParseFailed(x.to_string()))?;
-
-        m.java_version
-            .ok_or_else(|| AppError::ManifestParseFailed("Java version missing in inherited manifest".to_string()))?
-            .clone()
+    let inherited_version = version.get_inherited();
+    info!("Detected inherited version is {}", inherited_version.id);
+    if !inherited_version.is_installed() {
+        download_from_manifest(client, &inherited_version.id, &manifest, mirrors).await?;
+    }
+    report_stage(client, "Downloading version...", app_handle);
+    let downloadable_version = if version_loader.base == VersionBase::VANILLA {
+        &version
     } else {
-        return Err(AppError::ManifestParseFailed("No Java version found in manifest".to_string()));
+        &inherited_version
     };
 
-    download_java(
-        &java_version.component,
-        &java_version.major_version.to_string(),
-        logger,
-        &mirror,
-    ).await?;
-
-    download_libraries(&json.libraries, &id, app_handle, logger, &mirror).await?;
-
-    if let Some(downloads) = &json.downloads {
-        if let Some(client_download) = downloads.get("client") {
-            info!("Downloading client's process has started.");
-            update_download_status("Downloading version...", &app_handle);
-            download_client(client_download, &id, logger, &mirror).await?;
-        }
+    download_version(client, downloadable_version, name, app_handle, logger, settings).await?;
+    if inherited_version.id != version.id {
+        download_version(client, &version, name, app_handle, logger, settings).await?;
     }
-
-    if let Some(asset_index) = &json.asset_index {
-        info!("Downloading assets process has started.");
-        update_download_status("Downloading assets...", &app_handle);
-        download_assets(asset_index, logger, &mirror, app_handle).await?;
+    if let Some(installer) = pending_installer {
+        let result = run_processors(client, &installer, app_handle).await;
+        remove_if_exists(&installer)?;
+        result?;
     }
+    update_download_status("", app_handle);
+    Ok(version)
+}
 
-    if let Some(logging) = &json.logging {
-        info!("Downloading logger files process has started.");
-        let filename = logging.client.file.url.split("/").last().unwrap_or("logging.xml");
-        download_file_if_not_exists(
-            &get_version_directory(id).join(filename),
-            logging.client.file.url.clone(),
-            logging.client.file.sha1.as_str(),
-            logging.client.file.size,
-        )
-            .await?;
+/// Shows the current step of an installation, both on the legacy progress event and on the job it belongs to.
+pub(crate) fn report_stage(client: &HttpClient, text: &str, app_handle: &AppHandle) {
+    update_download_status(text, app_handle);
+    if let Some(job) = client.job() {
+        job.set_stage(text);
     }
-
-    update_download(100, "Done", app_handle);
-    Ok(())
 }
-async fn download_assets(
-    value: &AssetIndex,
-    logger: &UnboundedSender<LogLine>,
-    mirror: &Mirror,
+
+pub async fn download_version(
+    client: &HttpClient,
+    version: &MinecraftVersion,
+    name: &String,
     app_handle: &AppHandle,
+    _logger: &UnboundedSender<LogLine>,
+    settings: &DownloadSettings,
 ) -> Result<(), AppError> {
-    let id = &value.id;
-    let url = mirror.parse_url(&value.url);
-    let total_size = value.total_size;
-    let hash = value.sha1.as_str();
-    let asset_index_path = get_assets_directory()
-        .join("indexes")
-        .join(format!("{id}.json"))
-        .to_string_lossy()
-        .into_owned();
-
-    download_file_if_not_exists(
-        &PathBuf::from(&asset_index_path),
-        url.to_string(),
-        hash,
-        total_size,
-    ).await?;
+    let name = if name == "" { &version.id } else { name };
 
-    let content = fs::read_to_string(PathBuf::from(&asset_index_path))
-        .map_err(|e| AppError::FileReadFailed(format!("Failed to read asset index: {}", e)))?;
+    info!("Downloading version {} with name of {name}", &version.id);
+    let content = fs::read_to_string(PathBuf::from(version.get_json()))
+        .map_err(|x| AppError::FileReadFailed(format!("Failed to read version JSON: {}", x)))?;
+    let json: MinecraftManifestVersion =
+        serde_json::from_str(&content).map_err(|x| AppError::JsonParseFailed(x.to_string()))?;
 
-    let json: AssetObjects = serde_json::from_str(content.as_str())
-        .map_err(|e| AppError::JsonParseFailed(format!("Asset index isn't well formatted: {}", e)))?;
+    report_stage(client, "Resolving files...", app_handle);
+    let mut plan = plan_version(client, &json, settings).await?;
+    plan.check_installed();
+    execute_plan(client, &plan, app_handle, settings).await?;
 
-    let url_template = "https://resources.download.minecraft.net/{id}/{hash}";
-    let total_objects = json.objects.len();
+    update_download(100, "Done", app_handle);
+    Ok(())
+}
 
-    for (i, (_name, asset_entry)) in json.objects.iter().enumerate() {
-        let hash = &asset_entry.hash;
-        let prefix_id = hash[0..2].to_string();
-        let size = asset_entry.size;
-        let url = mirror.parse_url(
-            &url_template
-                .replace("{id}", prefix_id.as_str())
-                .replace("{hash}", hash),
-        );
-        let path = get_assets_directory()
-            .join("objects")
-            .join(prefix_id.as_str())
-            .join(hash);
+/// Downloads the files of the plan that `check_installed` found missing, then extracts its natives, lays out its legacy
+/// assets and marks its runtimes as installed.
+pub async fn execute_plan(
+    client: &HttpClient,
+    plan: &InstallPlan,
+    app_handle: &AppHandle,
+    settings: &DownloadSettings,
+) -> Result<(), AppError> {
+    let stages: [(&str, &[PlannedFileKind]); 4] = [
+        (
+            "Downloading Java...",
+            &[PlannedFileKind::VersionJson, PlannedFileKind::Installer, PlannedFileKind::JavaRuntime],
+        ),
+        ("Downloading libraries...", &[PlannedFileKind::Library, PlannedFileKind::Native]),
+        ("Downloading version...", &[PlannedFileKind::Client, PlannedFileKind::Logging]),
+        ("Downloading assets...", &[PlannedFileKind::AssetIndex, PlannedFileKind::Asset]),
+    ];
+    for (stage, kinds) in stages {
+        let tasks = plan.missing(kinds);
+        if tasks.is_empty() {
+            continue;
+        }
+        info!("{stage} {} files of {}", tasks.len(), plan.version);
+        report_stage(client, stage, app_handle);
+        download_all(client, tasks, settings, Some(app_handle)).await?;
+    }
 
-        update_download_bar((i * 100 / total_objects) as i64, app_handle);
-        download_file_if_not_exists(&path, url, hash.as_str(), size).await?;
+    for runtime in &plan.runtimes {
+        install_runtime_archive(client, &runtime.component, &runtime.version, settings).await?;
+        mark_runtime_executables(&runtime.component, &runtime.version)?;
+        write_java_release(&runtime.component, &runtime.version)?;
+    }
+    for native in plan.files.iter().filter(|x| x.kind == PlannedFileKind::Native) {
+        extract_natives(native, &plan.version)?;
+    }
+    for index in plan.files.iter().filter(|x| x.kind == PlannedFileKind::AssetIndex) {
+        if let Some(id) = index.path.file_stem() {
+            materialize_assets(&id.to_string_lossy(), false)?;
+        }
     }
     Ok(())
 }
 
 pub async fn download_file_if_not_exists(
+    client: &HttpClient,
     path: &PathBuf,
     url: String,
     hash: &str,
     size: u64,
+    mirrors: &[Mirror],
 ) -> Result<(), AppError> {
     let path_str = path.to_string_lossy().into_owned();
     if hash != "" {
         info!("SHA1 was detected. performing file chek!");
-        if !verify_file_existence_with_sha(path, hash)? {
+        if verify_file_existence_with_sha(path, hash)? {
+            add_to_store(path, hash)?;
+            count_skipped(client, size);
+        } else if link_from_store(hash, path)? {
+            info!("{} was linked from the store", path.display());
+            count_skipped(client, size);
+        } else {
             info!("Invalid hash was found!");
-            download_file(url, &path_str).await?;
+            download_file_from_mirrors(client, &url, path, hash, size, mirrors).await?;
+            add_to_store(path, hash)?;
         }
         return Ok(());
     }
     if !verify_file_existence_with_size(&path_str, size)? {
-        download_file(url, &path_str).await?;
+        download_file_from_mirrors(client, &url, path, "", size, mirrors).await?;
+    } else {
+        count_skipped(client, size);
     }
     Ok(())
 }
 
+/// Downloads `url` through the given mirrors in order, moving on to the next one (and finally the origin)
+/// whenever a mirror responds with an error or serves a file that doesn't match its hash.
+pub async fn download_file_from_mirrors(
+    client: &HttpClient,
+    url: &str,
+    dest: &Path,
+    hash: &str,
+    size: u64,
+    mirrors: &[Mirror],
+) -> Result<(), AppError> {
+    let mut last_error = None;
+    for (source, candidate) in candidate_urls(url, mirrors) {
+        match download_file_checked(client, candidate.clone(), dest, hash, size).await {
+            Ok(()) => {
+                info!("{} was served by {source}", dest.display());
+                return Ok(());
+            }
+            Err(e) if should_fail_over(&e) => {
+                info!("{source} failed to serve {candidate}: {e}");
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or(AppError::DownloadFailed))
+}
+
+/// Files that are already in place still count towards the job's progress.
+fn count_skipped(client: &HttpClient, size: u64) {
+    if let Some(job) = client.job() {
+        job.add_bytes(size);
+    }
+}
+
 pub async fn download_from_manifest(
+    client: &HttpClient,
     id: &String,
     manifest: &Manifest,
-    mir: &Mirror,
+    mirrors: &[Mirror],
 ) -> Result<(), AppError> {
     let version = manifest
         .versions
@@ -199,224 +307,238 @@ pub async fn download_from_manifest(
         .find(|v| &v.id == id)
         .ok_or_else(|| AppError::ManifestParseFailed(format!("Couldn't find version in manifest. {id}")))?;
 
-    let version_url = mir.parse_url(&version.url);
-    let dest = get_version_directory(id)
-        .join(format!("{}.json", id))
-        .to_string_lossy()
-        .into_owned();
-
-    download_file(version_url.to_string(), &dest).await
+    let dest = get_version_directory(id).join(format!("{}.json", id));
+    download_file_from_mirrors(client, &version.url, &dest, "", 0, mirrors).await
 }
 
-async fn download_client(
-    value: &DownloadDetail,
-    version: &String,
-    logger: &UnboundedSender<LogLine>,
-    mirror: &Mirror,
-) -> Result<(), AppError> {
-    let size = value.size;
-    let url = mirror.parse_url(&value.url);
-    let path = get_versions_directory()
-        .join(&version)
-        .join(format!("{}.jar", version));
-    let hash = value.sha1.as_str();
-    download_file_if_not_exists(&path, url.to_string(), hash, size).await
+fn extract_natives(native: &PlannedFile, version: &String) -> Result<(), AppError> {
+    extract_into_store(&native.path, &get_natives_directory(version), &native.exclude)
 }
 
-async fn download_libraries(
-    libraries: &[Library],
-    version: &String,
-    app_handle: &AppHandle,
-    logger: &UnboundedSender<LogLine>,
-    mirror: &Mirror,
-) -> Result<(), AppError> {
-    let libraries_path = get_libraries_directory();
-
-    for (library_index, library) in libraries.iter().enumerate() {
-        if library.downloads.is_none() {
-            let name = library.name.replace(":", "/");
-            let path = fetch_library_path(&name)?;
-            if name.starts_with_lower_case("net/minecraft") {
-                let url = mirror.parse_url(&format!("https://libraries.minecraft.net/{path}"));
-                let full_path = get_libraries_directory().join(path);
-                download_file_if_not_exists(&full_path, url, "", 0).await?;
-            } else {
-                let urls = fetch_unofficial_library_repos(&path);
-                for url in urls {
-                    let full_path = get_libraries_directory().join(&path);
-                    let client = Client::builder()
-                        .connect_timeout(Duration::from_secs(3))
-                        .build()
-                        .map_err(|e| AppError::Internal(e.to_string()))?;
-
-                    if client
-                        .head(url.clone())
-                        .send()
-                        .await
-                        .map_err(|x| AppError::NetworkRequestFailed(x.to_string()))?
-                        .status()
-                        .is_success()
-                    {
-                        download_file_if_not_exists(&full_path, url, "", 0).await?;
-                    }
-                }
-            }
-            continue;
-        }
-
-        let downloads = library.downloads.as_ref().ok_or_else(|| AppError::ManifestParseFailed("Downloads missing".to_string()))?;
-        if downloads.artifact.is_none() {
-            download_classifiers(downloads.classifiers.as_ref(), version, mirror).await?;
-            continue;
-        }
-
-        let library_artifact = downloads
-            .artifact
-            .as_ref()
-            .ok_or_else(|| AppError::ManifestParseFailed("Parsing library downloads failed".to_string()))?;
+fn download_file_async(client: &HttpClient, url: String, dest: String) -> Result<(), AppError> {
+    block_on(async { download_file(client, url, &dest).await })
+}
+fn download_file_async_thread(client: &HttpClient, url: String, dest: String) -> Result<(), AppError> {
+    block_on(async { download_file(client, url, &dest).await })
+}
 
-        let library_path = if let Some(path) = &library_artifact.path {
-            path.to_string()
-        } else {
-            let args = library.name.split(":").collect::<Vec<&str>>();
-            if args.len() < 3 {
-                return Err(AppError::ManifestParseFailed(format!("Invalid library name: {}", library.name)));
-            }
-            let group_id = args[0].replace(".", "/");
-            let artifact = args[1];
-            let version = args[2];
-            format!("{group_id}/{artifact}/{version}/{artifact}-{version}.jar")
-        };
-
-        update_download(
-            (library_index * 100 / libraries.len()) as i64,
-            format!("Downloading {}", library.name).as_str(),
-            app_handle,
-        );
+pub async fn download_file(client: &HttpClient, url: String, dest: &String) -> Result<(), AppError> {
+    download_file_checked(client, url, &PathBuf::from(dest), "", 0).await
+}
 
-        let os = get_current_os();
-        let rules = fetch_rules(library.rules.as_ref());
+/// The destinations being downloaded right now. two jobs needing the same file take turns, rather than both
+/// appending to its `.part` file.
+static DESTINATION_LOCKS: LazyLock<std::sync::Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>> =
+    LazyLock::new(Default::default);
+
+async fn lock_destination(dest: &Path) -> OwnedMutexGuard<()> {
+    let lock = {
+        let mut locks = DESTINATION_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+        // Locks only the table still holds belong to downloads that are over.
+        locks.retain(|_, x| Arc::strong_count(x) > 1);
+        locks.entry(dest.to_path_buf()).or_default().clone()
+    };
+    lock.lock_owned().await
+}
 
-        download_classifiers(downloads.classifiers.as_ref(), version, mirror).await?;
+/// Returns the path that a file gets streamed into before it's verified and moved to `dest`.
+pub fn get_part_path(dest: &Path) -> PathBuf {
+    let mut file_name = dest.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".part");
+    dest.with_file_name(file_name)
+}
 
-        if rules.allowed_oses.contains(&os) && !rules.disallowed_oses.contains(&os) {
-            let path = libraries_path.join(&library_path.as_str());
-            let hash = &library_artifact.sha1.clone().unwrap_or_default();
-            download_file_if_not_exists(
-                &path,
-                mirror.parse_url(&library_artifact.url),
-                hash.as_str(),
-                library_artifact.size.unwrap_or(0),
-            ).await?;
+/// Streams `url` into a `.part` file next to `dest` and moves it into place once it matches the given SHA1/size.
+///
+/// If a `.part` file is left over from an earlier attempt, the download resumes from where it stopped through
+/// an HTTP Range request. an empty hash or a size of 0 skips the matching check.
+pub async fn download_file_checked(
+    client: &HttpClient,
+    url: String,
+    dest: &Path,
+    hash: &str,
+    size: u64,
+) -> Result<(), AppError> {
+    if let Some(dest_folder) = dest.parent() {
+        if !dest_folder.as_os_str().is_empty() && !exists(dest_folder).unwrap_or(false) {
+            create_dir_all(dest_folder).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
         }
     }
-    Ok(())
+    let _lock = lock_destination(dest).await;
+    // Another job may have just finished the very same file while this one was waiting for it.
+    if !hash.is_empty() && verify_file_existence_with_sha(dest, hash)? {
+        return Ok(());
+    }
+    let job = client.job();
+    if let Some(job) = job {
+        job.checkpoint().await?;
+        job.set_current_file(&dest.file_name().unwrap_or_default().to_string_lossy());
+    }
+
+    // The bytes of an attempt that doesn't end up in place are taken back off the job, since a retry through
+    // another mirror counts them again.
+    let mut counted = 0;
+    let result = download_part(client, &url, dest, hash, size, &mut counted).await;
+    if let (Err(_), Some(job)) = (&result, job) {
+        job.remove_bytes(counted);
+    }
+    result
 }
 
-async fn download_classifiers(
-    classifiers: Option<&HashMap<String, LibraryArtifact>>,
-    version: &String,
-    mirror: &Mirror,
+/// Streams `url` into the `.part` file of `dest`, picking up where an earlier attempt stopped, and moves it into place
+/// once it matches the expected size and SHA1. `counted` keeps track of the bytes added to the job's progress.
+async fn download_part(
+    client: &HttpClient,
+    url: &str,
+    dest: &Path,
+    hash: &str,
+    size: u64,
+    counted: &mut u64,
 ) -> Result<(), AppError> {
-    let classifiers_map = match classifiers {
-        Some(c) => c,
-        None => return Ok(()),
+    let part_path = get_part_path(dest);
+    let job = client.job();
+    let mut add_bytes = |bytes: u64| {
+        if let Some(job) = job {
+            job.add_bytes(bytes);
+            *counted += bytes;
+        }
     };
 
-    let os = get_current_os();
-    let mut natives = classifiers_map.get(&format!("natives-{os}"));
-    if natives.is_none() && os == "windows" {
-        natives = classifiers_map.get(&format!("natives-{os}-64"));
-    }
-
-    if let Some(val) = natives {
-        let url = mirror.parse_url(&val.url.to_string());
-        let url_https_less = url.replace("https://", "").replace("http://", "");
-        let path = if let Some(p) = &val.path {
-            p.to_string()
-        } else {
-            let url_args = url_https_less.split("/").collect::<Vec<&str>>();
-            url_https_less.replace(url_args[0], "")
-        };
+    let mut hasher = Sha1::new();
+    let mut downloaded = resume_part_file(&part_path, size, &mut hasher).await?;
 
-        let full_path = get_libraries_directory().join(path);
-        let size = val.size.unwrap_or(0);
-        let hash = val.sha1.clone().unwrap_or_default();
-        download_file_if_not_exists(&full_path, url.to_string(), hash.as_str(), size).await?;
+    if size != 0 && downloaded >= size {
+        add_bytes(downloaded);
+    } else {
+        let mut request = client.get(url);
+        if downloaded > 0 {
+            info!("Resuming {url} from byte {downloaded}");
+            request = request.header(RANGE, format!("bytes={downloaded}-"));
+        }
+        let mut resp = client.send(url, request).await?;
+
+        if resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            info!("{url} can't be resumed from byte {downloaded}, starting over.");
+            remove_part_file(&part_path).await?;
+            resp = client.send(url, client.get(url)).await?;
+            downloaded = 0;
+            hasher = Sha1::new();
+        }
+        let mut resp = check_status(url, resp)?;
+        if downloaded > 0 && resp.status() != StatusCode::PARTIAL_CONTENT {
+            info!("{url} doesn't support resuming, starting over.");
+            downloaded = 0;
+            hasher = Sha1::new();
+        }
+        add_bytes(downloaded);
 
-        let file_path = full_path.to_string_lossy().into_owned();
-        let file = File::open(&file_path).map_err(|e| AppError::FileReadFailed(format!("Failed to open classifier: {}", e)))?;
-        let natives_path = get_natives_directory(version);
+        info!(
+            "Downloading {url} to {} with response of {}",
+            dest.display(),
+            resp.content_length().unwrap_or(0)
+        );
 
-        if !exists(&natives_path).unwrap_or(false) {
-            create_dir_all(&natives_path).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+        let mut out = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(downloaded > 0)
+            .truncate(downloaded == 0)
+            .open(&part_path)
+            .await
+            .map_err(|e| AppError::FileCreateFailed(format!("Unable to create file at {}: {}", part_path.display(), e)))?;
+
+        while let Some(chunk) = resp.chunk().await.map_err(|e| classify_error(url, &e))? {
+            client.throttle(chunk.len() as u64).await;
+            hasher.update(&chunk);
+            out.write_all(&chunk)
+                .await
+                .map_err(|e| AppError::FileWriteFailed(e.to_string()))?;
+            downloaded += chunk.len() as u64;
+            add_bytes(chunk.len() as u64);
+            if let Some(job) = job {
+                // Whatever is written so far stays in the .part file, so a paused or cancelled job can pick it up later.
+                if let Err(e) = job.checkpoint().await {
+                    out.flush().await.map_err(|e| AppError::FileWriteFailed(e.to_string()))?;
+                    return Err(e);
+                }
+            }
         }
+        out.flush().await.map_err(|e| AppError::FileWriteFailed(e.to_string()))?;
+    }
 
-        extract(file, &natives_path, false)
-            .map_err(|_| AppError::ZipExtractionFailed("Zip extraction of classifier failed".to_string()))?;
+    if size != 0 && downloaded != size {
+        remove_part_file(&part_path).await?;
+        return Err(AppError::FileVerificationFailed(format!(
+            "{url} was {downloaded} bytes while {size} bytes was expected"
+        )));
+    }
+    let computed_hash = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    if hash != "" && !computed_hash.eq_ignore_ascii_case(hash) {
+        remove_part_file(&part_path).await?;
+        return Err(AppError::FileVerificationFailed(format!(
+            "{url} has the SHA1 of {computed_hash} while {hash} was expected"
+        )));
     }
 
+    tokio::fs::rename(&part_path, dest)
+        .await
+        .map_err(|e| AppError::FileRenameFailed(format!("{}: {}", dest.display(), e)))?;
     Ok(())
 }
 
-fn download_file_async(url: String, dest: String) -> Result<(), AppError> {
-    block_on(async { download_file(url, &dest).await })
-}
-fn download_file_async_thread(url: String, dest: String) -> Result<(), AppError> {
-    block_on(async { download_file(url, &dest).await })
-}
-
-pub async fn download_file(url: String, dest: &String) -> Result<(), AppError> {
-    let resp = reqwest::get(&url)
+/// Feeds an existing `.part` file into the hasher and returns how many bytes of it can be kept.
+async fn resume_part_file(part_path: &Path, size: u64, hasher: &mut Sha1) -> Result<u64, AppError> {
+    let Ok(mut part) = tokio::fs::File::open(part_path).await else {
+        return Ok(0);
+    };
+    let length = part
+        .metadata()
         .await
-        .map_err(|_| AppError::DownloadFailed)?;
-
-    info!(
-        "Downloading {url} to {dest} with response of {}",
-        resp.content_length().unwrap_or(0)
-    );
-
-    let dest_folder = PathBuf::from(dest)
-        .parent()
-        .map(|p| p.to_string_lossy().into_owned())
-        .unwrap_or_default();
-
-    if !dest_folder.is_empty() && !exists(&dest_folder).unwrap_or(false) {
-        create_dir_all(&dest_folder).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+        .map_err(|e| AppError::FileReadFailed(e.to_string()))?
+        .len();
+    if size != 0 && length > size {
+        drop(part);
+        remove_part_file(part_path).await?;
+        return Ok(0);
     }
 
-    let mut out = File::create(&dest).map_err(|e| AppError::FileCreateFailed(format!("Unable to create file at {}: {}", dest, e)))?;
-    let bytes = resp.bytes().await.map_err(|e| AppError::DownloadFailed)?;
-    out.write_all(&bytes).map_err(|e| AppError::FileWriteFailed(e.to_string()))?;
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let bytes_read = part
+            .read(&mut buffer)
+            .await
+            .map_err(|e| AppError::FileReadFailed(format!("Failed to read file chunk: {}", e)))?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+    Ok(length)
+}
 
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        if let Ok(metadata) = std::fs::metadata(&dest) {
-            let mut permissions = metadata.permissions();
-            permissions.set_mode(0o755); // rwxr-xr-x
-            set_permissions(&dest, permissions)
-                .map_err(|x| AppError::AccessDenied(x.to_string()))?;
+async fn remove_part_file(part_path: &Path) -> Result<(), AppError> {
+    match tokio::fs::remove_file(part_path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(AppError::FileDeleteFailed(e.to_string()))
         }
+        _ => Ok(()),
     }
-    Ok(())
 }
 
 pub async fn get_available_forge_versions(
+    client: &HttpClient,
     version_id: &String,
-    mirror: &Mirror,
+    settings: &DownloadSettings,
 ) -> Result<Vec<String>, AppError> {
     let mut global_cache = GLOBAL_CACHE.lock().await;
     if global_cache.forge.is_none() {
-        let url = "https://files.minecraftforge.net/net/minecraftforge/forge/maven-metadata.json"
-            .parse_mirror(&mirror);
+        let url = "https://files.minecraftforge.net/net/minecraftforge/forge/maven-metadata.json";
 
-        let map: HashMap<String, Vec<String>> = reqwest::get(url)
-            .await
-            .map_err(|x| AppError::NetworkRequestFailed(x.to_string()))?
-            .json()
-            .await
-            .map_err(|x| AppError::NetworkRequestFailed(x.to_string()))?;
+        let map: HashMap<String, Vec<String>> = get_cached_json(client, url, settings).await?;
         global_cache.forge = Some(map);
     }
     let map = &global_cache.forge;
@@ -428,6 +550,56 @@ pub async fn get_available_forge_versions(
         .unwrap_or_default())
 }
 
+/// NeoForge versions are named after the Minecraft version they target, e.g. `21.1.77` is built for 1.21.1 and
+/// `26.1.0.3` for 26.1. returns `None` for the ones that don't follow the scheme, like April Fools' builds.
+pub fn neoforge_minecraft_version(version: &str) -> Option<String> {
+    let numbers = version.split(['-', '+']).next()?;
+    let parts = numbers
+        .split('.')
+        .map(|x| x.parse::<u32>().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    let (major, minor) = (*parts.first()?, *parts.get(1)?);
+    if major == 0 {
+        return None;
+    }
+    if major < 26 {
+        return Some(match minor {
+            0 => format!("1.{major}"),
+            _ => format!("1.{major}.{minor}"),
+        });
+    }
+    match parts.get(2)? {
+        0 => Some(format!("{major}.{minor}")),
+        patch => Some(format!("{major}.{minor}.{patch}")),
+    }
+}
+
+pub fn neoforge_installer_url(version: &str) -> String {
+    format!("https://maven.neoforged.net/releases/net/neoforged/neoforge/{version}/neoforge-{version}-installer.jar")
+}
+
+/// Lists the NeoForge versions built for the given Minecraft version, oldest first.
+pub async fn get_available_neoforge_versions(
+    client: &HttpClient,
+    version_id: &String,
+    settings: &DownloadSettings,
+) -> Result<Vec<String>, AppError> {
+    let mut global_cache = GLOBAL_CACHE.lock().await;
+    if global_cache.neoforge.is_none() {
+        let url = "https://maven.neoforged.net/api/maven/versions/releases/net/neoforged/neoforge";
+
+        let metadata: NeoForgeVersions = get_cached_json(client, url, settings).await?;
+        global_cache.neoforge = Some(metadata.versions);
+    }
+    Ok(global_cache
+        .neoforge
+        .iter()
+        .flatten()
+        .filter(|x| neoforge_minecraft_version(x).as_ref() == Some(version_id))
+        .cloned()
+        .collect())
+}
+
 pub async fn fetch_forge_mirrors(content: String) -> Vec<String> {
     let mut vec = Vec::new();
     for line in content.split("\n") {
@@ -440,17 +612,43 @@ pub async fn fetch_forge_mirrors(content: String) -> Vec<String> {
     vec
 }
 
+/// Installs the given NeoForge version, returning its installer for the processors to run later on.
+pub async fn download_neoforge_version(
+    client: &HttpClient,
+    version: &String,
+    app_handle: &AppHandle,
+    settings: &DownloadSettings,
+    ver: &mut String,
+) -> Result<Option<PathBuf>, AppError> {
+    let temp_dir = client.temp_directory();
+    create_dir_all(&temp_dir).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    let path = temp_dir.join(format!("neoforge-{version}-installer.jar"));
+    download_file_from_mirrors(client, &neoforge_installer_url(version), &path, "", 0, &settings.mirrors).await?;
+
+    match install_forge(client, &path, app_handle, settings).await {
+        Ok(id) => *ver = id,
+        Err(e) => {
+            remove_if_exists(&path)?;
+            return Err(e);
+        }
+    }
+    Ok(Some(path))
+}
+
+/// Installs the given Forge version. installers of modern versions are returned, their processors are left to run
+/// once the vanilla version is downloaded.
 pub async fn download_forge_version(
+    client: &HttpClient,
     version: &String,
     app_handle: &AppHandle,
     logger: &UnboundedSender<LogLine>,
-    mirror: &Mirror,
+    settings: &DownloadSettings,
     ver: &mut String,
-) -> Result<(), AppError> {
-    let url = format!("https://maven.minecraftforge.net/net/minecraftforge/forge/{version}/forge-{version}-installer.jar").parse_mirror(mirror);
-    let launcher_dir = get_falcon_launcher_directory();
+) -> Result<Option<PathBuf>, AppError> {
+    let mirrors = &settings.mirrors;
+    let url = format!("https://maven.minecraftforge.net/net/minecraftforge/forge/{version}/forge-{version}-installer.jar");
     info!("{}", url);
-    let mut path = launcher_dir.join("temp");
+    let mut path = client.temp_directory();
     let mut path_str = path.to_string_lossy().into_owned();
     info!("{}", path_str);
 
@@ -460,7 +658,7 @@ pub async fn download_forge_version(
 
     path = path.join(format!("forge-{version}-installer.jar"));
     path_str = path.to_string_lossy().into_owned();
-    download_file(url, &path_str).await?;
+    download_file_from_mirrors(client, &url, &path, "", 0, mirrors).await?;
 
     let version_args = version.split("-").collect::<Vec<&str>>();
     let mc_version = version_args.first().ok_or_else(|| AppError::ManifestParseFailed("Invalid Forge version format".to_string()))?;
@@ -468,28 +666,14 @@ pub async fn download_forge_version(
 
     if !is_legacy(&version) {
         info!("DEBUG: Non legacy version detected!",);
-        download_java(&"jre-legacy".to_string(), &"8".to_string(), logger, mirror).await?;
-        let jdk_8 = get_java("jre-legacy".to_string())?;
-
-        let mut child = Command::new(jdk_8.get_bin_file().display().to_string())
-            .arg("-jar")
-            .arg(&path_str)
-            .arg("--installClient")
-            .arg(get_minecraft_directory().display().to_string())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .current_dir(get_temp_directory())
-            .spawn()
-            .map_err(|e| AppError::Internal(format!("Failed to spawn Forge installer: {}", e)))?;
-
-        let stderr = child.stderr.take().ok_or_else(|| AppError::Internal("Failed to capture stderr".to_string()))?;
-        spawn_thread(stderr, format!("forge_installer_{version}"));
-        generate_stdout(&mut child, format!("forge_installer_{version}"))?;
-
-        let _ = child.wait_with_output();
-        fs::remove_dir_all(launcher_dir.join("temp")).map_err(|e| AppError::FileDeleteFailed(e.to_string()))?;
-
-        return Ok(());
+        match install_forge(client, &path, app_handle, settings).await {
+            Ok(id) => *ver = id,
+            Err(e) => {
+                remove_if_exists(&path)?;
+                return Err(e);
+            }
+        }
+        return Ok(Some(path));
     }
 
     info!("DEBUG: Legacy version detected!");
@@ -518,11 +702,8 @@ pub async fn download_forge_version(
             "{group_id}/{artifact}/{version_str}/{artifact_version}.jar"
         ));
 
-        let mirror_list = mirror.parse_url(&install_data.mirror_list);
-        if let Ok(resp) = reqwest::get(mirror_list).await {
-            if let Ok(text) = resp.text().await {
-                let _mirrors = fetch_forge_mirrors(text).await;
-            }
+        if let Ok(text) = get_text_from_mirrors(client, &install_data.mirror_list, mirrors).await {
+            let _mirrors = fetch_forge_mirrors(text).await;
         }
 
         create_dir_all(&full_path.parent().unwrap_or(Path::new("")))
@@ -599,10 +780,12 @@ pub async fn download_forge_version(
                     let hash = artifact.sha1.clone().unwrap_or_default();
                     let size = artifact.size.unwrap_or_default();
                     download_file_if_not_exists(
+                        client,
                         &PathBuf::from(full_path),
                         full_url,
                         hash.as_str(),
                         size,
+                        mirrors,
                     )
                         .await?;
                 }
@@ -617,12 +800,12 @@ pub async fn download_forge_version(
                 get_libraries_directory().to_string_lossy().into_owned(),
                 &library.name,
             )?;
-            download_file_if_not_exists(&PathBuf::from(full_path), full_url, "", 0).await?;
+            download_file_if_not_exists(client, &PathBuf::from(full_path), full_url, "", 0, mirrors).await?;
         }
     }
 
-    fs::remove_dir_all(launcher_dir.join("temp")).map_err(|e| AppError::FileDeleteFailed(e.to_string()))?;
-    Ok(())
+    remove_if_exists(&path)?;
+    Ok(None)
 }
 
 fn spawn_thread(stderr: ChildStderr, task_name: String) {
@@ -635,26 +818,18 @@ fn spawn_thread(stderr: ChildStderr, task_name: String) {
 }
 
 pub async fn download_fabric(
+    client: &HttpClient,
     version_loader: &VersionLoader,
     logger: &UnboundedSender<LogLine>,
-    mirror: &Mirror,
+    settings: &DownloadSettings,
 ) -> Result<(), AppError> {
+    let mirrors = &settings.mirrors;
     let loaders_url = "https://meta.fabricmc.net/v2/versions/loader";
     let installers_url = "https://meta.fabricmc.net/v2/versions/installer";
 
-    let loaders = reqwest::get(loaders_url)
-        .await
-        .map_err(|e| AppError::NetworkRequestFailed(e.to_string()))?
-        .json::<Vec<FabricLoader>>()
-        .await
-        .map_err(|e| AppError::NetworkRequestFailed(e.to_string()))?;
+    let loaders = get_cached_json::<Vec<FabricLoader>>(client, loaders_url, settings).await?;
 
-    let installers = reqwest::get(installers_url)
-        .await
-        .map_err(|e| AppError::NetworkRequestFailed(e.to_string()))?
-        .json::<Vec<FabricInstaller>>()
-        .await
-        .map_err(|e| AppError::NetworkRequestFailed(e.to_string()))?;
+    let installers = get_cached_json::<Vec<FabricInstaller>>(client, installers_url, settings).await?;
 
     let _loader = loaders
         .iter()
@@ -667,16 +842,20 @@ pub async fn download_fabric(
         .ok_or_else(|| AppError::VersionNotFound)?;
 
     let installer_path_download = convert_to_full_path(
-        get_temp_directory().to_string_lossy().into_owned(),
+        client.temp_directory().to_string_lossy().into_owned(),
         &stable_installer.maven,
     )?;
 
-    download_file(
-        stable_installer.url.to_string(),
-        &installer_path_download,
+    download_file_from_mirrors(
+        client,
+        &stable_installer.url,
+        Path::new(&installer_path_download),
+        "",
+        0,
+        mirrors,
     ).await?;
 
-    download_java(&"jre-legacy".to_string(), &"8".to_string(), logger, mirror).await?;
+    download_java(client, &"jre-legacy".to_string(), &"8".to_string(), logger, settings).await?;
     let jdk_8 = get_java("jre-legacy".to_string())?;
 
     let installer_path_buf = PathBuf::from(&installer_path_download);
@@ -711,6 +890,65 @@ pub async fn download_fabric(
     Ok(())
 }
 
+pub fn quilt_profile_url(version_loader: &VersionLoader) -> String {
+    format!(
+        "https://meta.quiltmc.org/v3/versions/loader/{}/{}/profile/json",
+        version_loader.get_quilt_version_id(),
+        version_loader.get_quilt_loader_id()
+    )
+}
+
+/// Quilt has no installer to run, its meta API generates the launcher profile the version JSON is made of.
+pub async fn download_quilt(
+    client: &HttpClient,
+    version_loader: &VersionLoader,
+    settings: &DownloadSettings,
+) -> Result<(), AppError> {
+    let mirrors = &settings.mirrors;
+    let mc_version = version_loader.get_quilt_version_id();
+    let manifest = load_version_manifest(client, settings).await?;
+    download_from_manifest(client, &mc_version, &manifest, mirrors).await?;
+
+    let profile: serde_json::Value = get_json_from_mirrors(client, &quilt_profile_url(version_loader), mirrors).await?;
+    let version_id = version_loader.get_installed_id();
+    create_dir_all(get_version_directory(&version_id)).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    let content = serde_json::to_string(&profile).map_err(|e| AppError::JsonParseFailed(e.to_string()))?;
+    fs::write(get_version_manifest(&version_id), content)
+        .map_err(|e| AppError::FileWriteFailed(format!("Failed to write {version_id}.json: {e}")))?;
+    Ok(())
+}
+
+pub async fn get_available_quilt_versions(
+    client: &HttpClient,
+    version_id: &String,
+    settings: &DownloadSettings,
+) -> Result<Vec<String>, AppError> {
+    let mut global_cache = GLOBAL_CACHE.lock().await;
+
+    if global_cache.quilt_mc_versions.is_none() {
+        let url = "https://meta.quiltmc.org/v3/versions/game";
+        let map: Vec<QuiltMinecraftVersion> = get_cached_json(client, url, settings).await?;
+        global_cache.quilt_mc_versions = Some(map);
+    }
+
+    if global_cache.quilt_loaders.is_none() {
+        let url = "https://meta.quiltmc.org/v3/versions/loader";
+        let map: Vec<QuiltLoader> = get_cached_json(client, url, settings).await?;
+        global_cache.quilt_loaders = Some(map);
+    }
+
+    let mc_versions = global_cache.quilt_mc_versions.clone().unwrap_or_default();
+    if !mc_versions.iter().any(|x| &x.version == version_id) {
+        return Ok(Vec::new());
+    }
+
+    let loaders = global_cache.quilt_loaders.clone().unwrap_or_default();
+    Ok(loaders
+        .into_iter()
+        .map(|loader| format!("{}-{}", version_id, loader.version))
+        .collect())
+}
+
 pub fn generate_stdout(child: &mut Child, task_name: String) -> Result<(), AppError> {
     let stdout = child.stdout.take().ok_or_else(|| AppError::Internal("Failed to open stdout".to_string()))?;
     std::thread::spawn(move || {
@@ -722,39 +960,28 @@ pub fn generate_stdout(child: &mut Child, task_name: String) -> Result<(), AppEr
     Ok(())
 }
 
-pub async fn get_available_fabric_versions(version_id: &String) -> Result<Vec<String>, AppError> {
+pub async fn get_available_fabric_versions(
+    client: &HttpClient,
+    version_id: &String,
+    settings: &DownloadSettings,
+) -> Result<Vec<String>, AppError> {
     let mut global_cache = GLOBAL_CACHE.lock().await;
 
     if global_cache.fabric_mc_versions.is_none() {
         let url = "https://meta.fabricmc.net/v2/versions/game";
-        let map: Vec<FabricMinecraftVersion> = reqwest::get(url)
-            .await
-            .map_err(|x| AppError::NetworkRequestFailed(x.to_string()))?
-            .json()
-            .await
-            .map_err(|x| AppError::NetworkRequestFailed(x.to_string()))?;
+        let map: Vec<FabricMinecraftVersion> = get_cached_json(client, url, settings).await?;
         global_cache.fabric_mc_versions = Some(map);
     }
 
     if global_cache.fabric_installers.is_none() {
         let url = "https://meta.fabricmc.net/v2/versions/installer";
-        let map: Vec<FabricInstaller> = reqwest::get(url)
-            .await
-            .map_err(|x| AppError::NetworkRequestFailed(x.to_string()))?
-            .json()
-            .await
-            .map_err(|x| AppError::NetworkRequestFailed(x.to_string()))?;
+        let map: Vec<FabricInstaller> = get_cached_json(client, url, settings).await?;
         global_cache.fabric_installers = Some(map);
     }
 
     if global_cache.fabric_loaders.is_none() {
         let url = "https://meta.fabricmc.net/v2/versions/loader";
-        let map: Vec<FabricLoader> = reqwest::get(url)
-            .await
-            .map_err(|x| AppError::NetworkRequestFailed(x.to_string()))?
-            .json()
-            .await
-            .map_err(|x| AppError::NetworkRequestFailed(x.to_string()))?;
+        let map: Vec<FabricLoader> = get_cached_json(client, url, settings).await?;
         global_cache.fabric_loaders = Some(map);
     }
 
@@ -772,4 +999,53 @@ pub async fn get_available_fabric_versions(version_id: &String) -> Result<Vec<St
     }
 
     Ok(result)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neoforge_versions_before_26_target_1_x() {
+        assert_eq!(neoforge_minecraft_version("21.1.77").as_deref(), Some("1.21.1"));
+        assert_eq!(neoforge_minecraft_version("20.4.237").as_deref(), Some("1.20.4"));
+        assert_eq!(neoforge_minecraft_version("21.0.167").as_deref(), Some("1.21"));
+    }
+
+    #[test]
+    fn neoforge_suffixes_are_ignored() {
+        assert_eq!(neoforge_minecraft_version("21.1.0-beta").as_deref(), Some("1.21.1"));
+        assert_eq!(neoforge_minecraft_version("20.2.3-beta+build").as_deref(), Some("1.20.2"));
+    }
+
+    #[test]
+    fn neoforge_versions_from_26_keep_the_year_scheme() {
+        assert_eq!(neoforge_minecraft_version("26.1.0.3").as_deref(), Some("26.1"));
+        assert_eq!(neoforge_minecraft_version("26.1.2.0").as_deref(), Some("26.1.2"));
+        assert_eq!(neoforge_minecraft_version("26.1"), None);
+    }
+
+    #[test]
+    fn neoforge_versions_off_the_scheme_have_no_target() {
+        assert_eq!(neoforge_minecraft_version("0.25w14craftmine.3-beta"), None);
+        assert_eq!(neoforge_minecraft_version("21"), None);
+        assert_eq!(neoforge_minecraft_version(""), None);
+    }
+
+    fn quilt(id: &str) -> VersionLoader {
+        VersionLoader {
+            id: id.to_string(),
+            base: VersionBase::QUILT,
+            date: String::new(),
+        }
+    }
+
+    #[test]
+    fn quilt_profiles_are_fetched_for_the_minecraft_and_loader_version() {
+        assert_eq!(
+            quilt_profile_url(&quilt("1.20.1-0.26.4-beta.1")),
+            "https://meta.quiltmc.org/v3/versions/loader/1.20.1/0.26.4-beta.1/profile/json"
+        );
+        assert_eq!(quilt("1.20.1-0.26.4-beta.1").get_installed_id(), "quilt-loader-0.26.4-beta.1-1.20.1");
+    }
+}
@@ -1,5 +1,7 @@
+use crate::models::config::LaunchOptions;
 use crate::models::error::AppError;
-use crate::services::utils;
+use crate::models::launch_context::{LaunchContext, QuickPlay};
+use crate::services::rule_engine::is_allowed_value;
 pub use crate::AppState;
 use serde_json::Value;
 use std::process::Command;
@@ -11,37 +13,27 @@ pub(crate) fn apply_dedicated_gpu_env(cmd: &mut Command) {
     cmd.env("__VK_LAYER_NV_optimus", "NVIDIA_only");
 }
 
-pub fn get_jvm_args(json: &Value) -> Vec<String> {
+/// The context the rules of a launch get checked against, with the features its options enable.
+pub fn build_launch_context(options: &LaunchOptions, quick_play: Option<&QuickPlay>) -> LaunchContext {
+    let mut context = LaunchContext::current()
+        .with_feature("is_demo_user", options.demo_mode.boolean())
+        .with_feature("has_custom_resolution", options.has_custom_resolution())
+        .with_feature("has_quick_plays_support", quick_play.is_some());
+    if let Some(quick_play) = quick_play {
+        context = context.with_feature(quick_play.feature(), true);
+    }
+    context
+}
+
+pub fn get_jvm_args(json: &Value, context: &LaunchContext) -> Vec<String> {
     let mut vec = Vec::new();
-    if let Some(arguments) = json.get("arguments") {
-        if let Some(jvm_rules) = arguments.get("jvm").and_then(|v| v.as_array()) {
-            for rule in jvm_rules {
-                if let Some(arg_str) = rule.as_str() {
-                    vec.push(arg_str.to_string());
-                } else if let Some(obj) = rule.as_object() {
-                    if let Some(value) = obj.get("value") {
-                        if utils::can_apply_rule(obj) {
-                            match value {
-                                Value::String(s) => vec.push(s.clone()),
-                                Value::Array(arr) => {
-                                    for item in arr {
-                                        if let Some(s) = item.as_str() {
-                                            vec.push(s.to_string());
-                                        }
-                                    }
-                                }
-                                _ => {}
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    if let Some(jvm_args) = json.pointer("/arguments/jvm").and_then(|v| v.as_array()) {
+        collect_arguments(jvm_args, context, &mut vec);
     }
     vec
 }
 
-pub fn get_launch_args(json: &Value) -> Result<Vec<String>, AppError> {
+pub fn get_launch_args(json: &Value, context: &LaunchContext) -> Result<Vec<String>, AppError> {
     if let Some(minecraft_args) = json.get("minecraftArguments") {
         let args_str = minecraft_args
             .as_str()
@@ -54,11 +46,35 @@ pub fn get_launch_args(json: &Value) -> Result<Vec<String>, AppError> {
             .and_then(|g| g.as_array())
             .ok_or_else(|| AppError::LaunchArgsNotFound("arguments.game is missing or not an array".to_string()))?;
 
-        Ok(game_args
-            .iter()
-            .filter_map(|v| v.as_str().map(|s| s.to_string()))
-            .collect())
+        let mut vec = Vec::new();
+        collect_arguments(game_args, context, &mut vec);
+        Ok(vec)
     } else {
         Err(AppError::LaunchArgsNotFound("Could not find launch arguments in the manifest".to_string()))
     }
-}
\ No newline at end of file
+}
+
+/// Plain strings are always taken, objects only when their rules allow them in the context. their value is either
+/// a single argument or an array of them.
+fn collect_arguments(arguments: &[Value], context: &LaunchContext, vec: &mut Vec<String>) {
+    for argument in arguments {
+        if let Some(arg_str) = argument.as_str() {
+            vec.push(arg_str.to_string());
+        } else if let Some(obj) = argument.as_object() {
+            if !is_allowed_value(obj.get("rules"), context) {
+                continue;
+            }
+            match obj.get("value") {
+                Some(Value::String(s)) => vec.push(s.clone()),
+                Some(Value::Array(arr)) => {
+                    for item in arr {
+                        if let Some(s) = item.as_str() {
+                            vec.push(s.to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use crate::models::downloader::AssetObjects;
+use crate::models::error::AppError;
+use crate::models::garbage::{GarbageKind, GarbageReport};
+use crate::models::install_plan::PlannedFileKind;
+use crate::models::versions::MinecraftVersion;
+use crate::services::directory_manager::{
+    get_assets_directory, get_java_dir, get_libraries_directory, get_object_store_directory, get_versions_directory,
+};
+use crate::services::forge_installer::processor_outputs_file;
+use crate::services::install_planner::plan_offline_launch;
+use crate::services::object_store::archive_hashes;
+use crate::services::utils::list_files;
+use log::info;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Library directories holding what Forge and NeoForge processors produce (the srg, extra and patched client jars
+/// go under `net/minecraft/client`), kept as a whole when a version doesn't record its outputs.
+const PROCESSOR_OUTPUT_DIRECTORIES: [&str; 3] = ["net/minecraftforge", "net/neoforged", "net/minecraft/client"];
+
+/// Everything the installed versions still refer to.
+#[derive(Default)]
+struct References {
+    files: HashSet<PathBuf>,
+    /// Names of the Java runtime components, kept as a whole.
+    runtimes: HashSet<String>,
+    /// SHA1s of the stored objects, whether they got hard linked or copied out of the store.
+    objects: HashSet<String>,
+    /// Directories nothing under gets collected, e.g. the processor outputs of versions that didn't record them.
+    kept_directories: Vec<PathBuf>,
+}
+
+impl References {
+    fn contains(&self, path: &Path) -> bool {
+        self.files.contains(path) || self.kept_directories.iter().any(|x| path.starts_with(x))
+    }
+}
+
+/// Finds the libraries, asset indexes, asset objects, Java runtimes and stored objects that no installed version
+/// refers to anymore, following `inheritsFrom`. they're only deleted when `delete` is set, otherwise the report
+/// just tells how much space deleting them would give back.
+pub async fn collect_garbage(delete: bool) -> Result<GarbageReport, AppError> {
+    let references = find_references().await?;
+    let mut report = GarbageReport::default();
+
+    let assets = get_assets_directory();
+    let sections = [
+        (get_libraries_directory(), GarbageKind::Library),
+        (assets.join("indexes"), GarbageKind::AssetIndex),
+        (assets.join("objects"), GarbageKind::Asset),
+    ];
+    for (directory, kind) in &sections {
+        for path in list_files(directory) {
+            if !references.contains(&path) {
+                let size = file_size(&path);
+                report.push(*kind, path, size);
+            }
+        }
+    }
+
+    if let Ok(entries) = get_java_dir().read_dir() {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if path.is_dir() && !references.runtimes.contains(&name) {
+                let size = list_files(&path).iter().map(|x| file_size(x)).sum();
+                report.push(GarbageKind::JavaRuntime, path, size);
+            }
+        }
+    }
+
+    collect_stored_objects(&get_object_store_directory(), &references.objects, &mut report);
+
+    info!(
+        "Found {} unreferenced files taking {} bytes",
+        report.files.len(),
+        report.reclaimable_bytes
+    );
+    if delete {
+        delete_garbage(&report)?;
+        for (directory, _) in &sections {
+            remove_empty_directories(directory);
+        }
+        remove_empty_directories(&get_object_store_directory());
+        report.deleted = true;
+    }
+    Ok(report)
+}
+
+async fn find_references() -> Result<References, AppError> {
+    let mut references = References::default();
+    let java_dir = get_java_dir();
+    let libraries_dir = get_libraries_directory();
+    let objects = get_assets_directory().join("objects");
+    let mut unrecorded_processors = false;
+
+    let Ok(entries) = get_versions_directory().read_dir() else {
+        return Ok(references);
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() || !has_json(&path) {
+            continue;
+        }
+        // A version that can't be read might still need its files, better to keep everything than guess.
+        let version = MinecraftVersion::from_folder(path.clone()).map_err(|_| {
+            AppError::ManifestParseFailed(format!(
+                "{} couldn't be read, refusing to collect garbage",
+                path.display()
+            ))
+        })?;
+
+        let outputs_file = processor_outputs_file(&version.id);
+        if outputs_file.is_file() {
+            let outputs = fs::read_to_string(&outputs_file).map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+            references.files.extend(outputs.lines().filter(|x| !x.is_empty()).map(|x| libraries_dir.join(x)));
+        } else if version.is_forge() {
+            unrecorded_processors = true;
+        }
+
+        let plan = plan_offline_launch(&version).await.map_err(|e| {
+            AppError::ManifestParseFailed(format!(
+                "The files of {} couldn't be resolved, refusing to collect garbage: {e}",
+                version.id
+            ))
+        })?;
+        for file in plan.files {
+            if !file.sha1.is_empty() {
+                references.objects.insert(file.sha1.to_lowercase());
+            }
+            match file.kind {
+                PlannedFileKind::JavaRuntime => {
+                    if let Some(component) = file.path.strip_prefix(&java_dir).ok().and_then(|x| x.iter().next()) {
+                        references.runtimes.insert(component.to_string_lossy().into_owned());
+                    }
+                }
+                PlannedFileKind::AssetIndex => {
+                    // The index is read no matter its hash, a modified index still refers to its objects.
+                    let index = fs::read_to_string(&file.path)
+                        .ok()
+                        .and_then(|x| serde_json::from_str::<AssetObjects>(&x).ok());
+                    for object in index.iter().flat_map(|x| x.objects.values()) {
+                        let hash = &object.hash;
+                        references.files.insert(objects.join(&hash[0..2]).join(hash));
+                        references.objects.insert(hash.to_lowercase());
+                    }
+                    references.files.insert(file.path);
+                }
+                PlannedFileKind::Native if file.path.is_file() => {
+                    // Natives get extracted through the store, every file of the jar is an object of it.
+                    let hashes = archive_hashes(&file.path, &file.exclude).unwrap_or_default();
+                    references.objects.extend(hashes);
+                    references.files.insert(file.path);
+                }
+                _ => {
+                    references.files.insert(file.path);
+                }
+            }
+        }
+    }
+    if unrecorded_processors {
+        info!("A Forge version doesn't record its processor outputs, keeping every file they could be");
+        references.kept_directories = PROCESSOR_OUTPUT_DIRECTORIES.iter().map(|x| libraries_dir.join(x)).collect();
+    }
+    Ok(references)
+}
+
+/// Stored objects whose SHA1 no installed version refers to. objects are named after their hash, and the hash is
+/// what's compared rather than link counts, as the store falls back to copies when it can't hard link.
+fn collect_stored_objects(store: &Path, objects: &HashSet<String>, report: &mut GarbageReport) {
+    for path in list_files(store) {
+        let referenced = path
+            .file_name()
+            .map(|x| objects.contains(&x.to_string_lossy().to_lowercase()))
+            .unwrap_or(false);
+        if !referenced {
+            let size = file_size(&path);
+            report.push(GarbageKind::StoredObject, path, size);
+        }
+    }
+}
+
+fn delete_garbage(report: &GarbageReport) -> Result<(), AppError> {
+    for file in &report.files {
+        let result = if file.kind == GarbageKind::JavaRuntime {
+            fs::remove_dir_all(&file.path)
+        } else {
+            fs::remove_file(&file.path)
+        };
+        match result {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(AppError::FileDeleteFailed(format!("{}: {e}", file.path.display())));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn has_json(directory: &Path) -> bool {
+    directory
+        .read_dir()
+        .map(|entries| {
+            entries
+                .flatten()
+                .any(|x| x.path().extension().map(|e| e == "json").unwrap_or(false))
+        })
+        .unwrap_or(false)
+}
+
+/// Removes the directories left empty under `directory`, the directory itself stays.
+fn remove_empty_directories(directory: &Path) {
+    let Ok(entries) = directory.read_dir() else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            remove_empty_directories(&path);
+            // Fails for directories that still have something in them, which is exactly what should stay.
+            let _ = fs::remove_dir(&path);
+        }
+    }
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map(|x| x.len()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copied_store_objects_survive_a_collection() {
+        let store = std::env::temp_dir().join(format!("falcon-store-{}", uuid::Uuid::new_v4()));
+        let copied = "ab0aa7bd1c5b8e2d6a1e9b4f2c3d7e8f90a1b2c3";
+        let unreferenced = "cd5f2b7e8a9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e";
+        for hash in [copied, unreferenced] {
+            fs::create_dir_all(store.join(&hash[0..2])).unwrap();
+            // Written rather than linked, like `add_to_store` does when hard linking fails: one link each.
+            fs::write(store.join(&hash[0..2]).join(hash), hash).unwrap();
+        }
+
+        let mut report = GarbageReport::default();
+        collect_stored_objects(&store, &HashSet::from([copied.to_string()]), &mut report);
+        fs::remove_dir_all(&store).unwrap();
+
+        let collected = report.files.iter().map(|x| x.path.clone()).collect::<Vec<_>>();
+        assert_eq!(collected, vec![store.join("cd").join(unreferenced)]);
+        assert_eq!(report.files[0].kind, GarbageKind::StoredObject);
+    }
+
+    #[test]
+    fn kept_directories_cover_unrecorded_processor_outputs() {
+        let libraries = PathBuf::from("libraries");
+        let references = References {
+            kept_directories: PROCESSOR_OUTPUT_DIRECTORIES.iter().map(|x| libraries.join(x)).collect(),
+            ..Default::default()
+        };
+        let srg = libraries.join("net/minecraft/client/1.20.1-20230612.114412/client-1.20.1-20230612.114412-srg.jar");
+        assert!(references.contains(&srg));
+        assert!(references.contains(&libraries.join("net/neoforged/neoforge/21.0.1/neoforge-21.0.1-client.jar")));
+        assert!(!references.contains(&libraries.join("org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1.jar")));
+    }
+}
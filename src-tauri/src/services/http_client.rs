@@ -0,0 +1,213 @@
+use crate::models::config::{ProxyKind, ProxySettings};
+use crate::models::error::AppError;
+use crate::services::bandwidth_limiter::BandwidthLimiter;
+use crate::services::directory_manager::get_temp_directory;
+use crate::services::download_manager::DownloadJob;
+use crate::{LAUNCHER_NAME, LAUNCHER_VERSION};
+use reqwest::{Client, IntoUrl, NoProxy, Proxy, Response, StatusCode};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware, RequestBuilder};
+use reqwest_retry::policies::ExponentialBackoff;
+use reqwest_retry::{RetryError, RetryTransientMiddleware};
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+const MAX_RETRIES: u32 = 3;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The launcher's shared HTTP client, every network request should go through it.
+///
+/// Transient failures (connection errors, timeouts, 5xx and 429 responses) are retried with an exponential backoff.
+/// Cloning it is cheap since the inner clients are reference counted, and every clone follows `set_proxy`.
+#[derive(Clone)]
+pub struct HttpClient {
+    clients: Arc<RwLock<Clients>>,
+    job: Option<Arc<DownloadJob>>,
+    bandwidth: Arc<BandwidthLimiter>,
+}
+
+#[derive(Clone)]
+struct Clients {
+    client: ClientWithMiddleware,
+    plain: Client,
+}
+
+impl Clients {
+    fn new(proxy: &ProxySettings) -> Result<Self, AppError> {
+        let mut builder = Client::builder()
+            .user_agent(format!("{LAUNCHER_NAME}/{LAUNCHER_VERSION}"))
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(READ_TIMEOUT);
+        builder = match proxy.kind {
+            ProxyKind::SYSTEM => builder,
+            ProxyKind::NONE => builder.no_proxy(),
+            _ => builder.proxy(build_proxy(proxy)?),
+        };
+        let plain = builder
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to build the http client: {e}")))?;
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(MAX_RETRIES);
+        let client = ClientBuilder::new(plain.clone())
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+            .build();
+        Ok(Self { client, plain })
+    }
+}
+
+fn build_proxy(settings: &ProxySettings) -> Result<Proxy, AppError> {
+    let url = settings
+        .url()
+        .ok_or_else(|| AppError::InvalidProxy(format!("{:?} doesn't name a proxy", settings.kind)))?;
+    if settings.host.trim().is_empty() || settings.port == 0 {
+        return Err(AppError::InvalidProxy(format!("{url} is missing its host or port")));
+    }
+    let mut proxy = Proxy::all(&url).map_err(|e| AppError::InvalidProxy(format!("{url}: {e}")))?;
+    if !settings.username.is_empty() {
+        proxy = proxy.basic_auth(&settings.username, &settings.password);
+    }
+    Ok(proxy.no_proxy(NoProxy::from_string(&settings.bypass)))
+}
+
+impl HttpClient {
+    pub fn new(proxy: &ProxySettings) -> Result<Self, AppError> {
+        Ok(Self {
+            clients: Arc::new(RwLock::new(Clients::new(proxy)?)),
+            job: None,
+            bandwidth: Arc::new(BandwidthLimiter::new(0)),
+        })
+    }
+
+    /// Rebuilds the inner clients with the given proxy settings, requests that are already on their way keep
+    /// the old ones.
+    pub fn set_proxy(&self, proxy: &ProxySettings) -> Result<(), AppError> {
+        let clients = Clients::new(proxy)?;
+        *self.clients.write().unwrap_or_else(|e| e.into_inner()) = clients;
+        Ok(())
+    }
+
+    fn clients(&self) -> Clients {
+        self.clients.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// A handle on the same client whose downloads are accounted to the given job and follow its pause/cancel state.
+    pub fn with_job(&self, job: Arc<DownloadJob>) -> Self {
+        Self {
+            job: Some(job),
+            ..self.clone()
+        }
+    }
+
+    pub fn job(&self) -> Option<&Arc<DownloadJob>> {
+        self.job.as_ref()
+    }
+
+    /// Where installers and archives get downloaded to. every job has a directory of its own, so cleaning up after
+    /// one can't take away the files another one is still using.
+    pub fn temp_directory(&self) -> PathBuf {
+        match &self.job {
+            Some(job) => get_temp_directory().join(&job.id),
+            None => get_temp_directory(),
+        }
+    }
+
+    /// The global bandwidth cap, shared by every handle on this client.
+    pub fn bandwidth(&self) -> &BandwidthLimiter {
+        &self.bandwidth
+    }
+
+    /// Waits until `bytes` more fit under both the global cap and the cap of the job the client is attached to.
+    pub async fn throttle(&self, bytes: u64) {
+        self.bandwidth.consume(bytes).await;
+        if let Some(job) = &self.job {
+            job.bandwidth.consume(bytes).await;
+        }
+    }
+
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.clients().client.get(url)
+    }
+
+    pub fn head<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.clients().client.head(url)
+    }
+
+    /// The same client without the retry middleware, meant for quick probes where failing fast matters more.
+    pub fn without_retries(&self) -> Client {
+        self.clients().plain
+    }
+
+    /// Sends the request and classifies transport failures, the status code is left to the caller.
+    pub async fn send(&self, url: &str, request: RequestBuilder) -> Result<Response, AppError> {
+        request
+            .send()
+            .await
+            .map_err(|e| classify_middleware_error(url, e))
+    }
+
+    /// Sends a GET request to `url` and fails if the response isn't successful.
+    pub async fn fetch(&self, url: &str) -> Result<Response, AppError> {
+        let resp = self.send(url, self.get(url)).await?;
+        check_status(url, resp)
+    }
+
+    pub async fn get_text(&self, url: &str) -> Result<String, AppError> {
+        self.fetch(url)
+            .await?
+            .text()
+            .await
+            .map_err(|e| classify_error(url, &e))
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, AppError> {
+        let text = self.get_text(url).await?;
+        serde_json::from_str(&text)
+            .map_err(|e| AppError::JsonParseFailed(format!("Failed to parse the response of {url}: {e}")))
+    }
+}
+
+/// Turns unsuccessful responses into an `AppError::HttpStatus`.
+pub fn check_status(url: &str, resp: Response) -> Result<Response, AppError> {
+    if resp.status().is_success() {
+        Ok(resp)
+    } else {
+        Err(status_error(url, resp.status()))
+    }
+}
+
+pub fn status_error(url: &str, status: StatusCode) -> AppError {
+    AppError::HttpStatus(status.as_u16(), url.to_string())
+}
+
+/// Maps a reqwest error into the matching `AppError` so the frontend can tell timeouts, connection failures
+/// and bad responses apart.
+pub fn classify_error(url: &str, e: &reqwest::Error) -> AppError {
+    if let Some(status) = e.status() {
+        status_error(url, status)
+    } else if e.is_timeout() {
+        AppError::RequestTimedOut(url.to_string())
+    } else if e.is_connect() {
+        AppError::ConnectionFailed(format!("{url}: {e}"))
+    } else if e.is_decode() {
+        AppError::JsonParseFailed(format!("Failed to decode the response of {url}: {e}"))
+    } else {
+        AppError::NetworkRequestFailed(format!("{url}: {e}"))
+    }
+}
+
+fn classify_middleware_error(url: &str, e: reqwest_middleware::Error) -> AppError {
+    match e {
+        reqwest_middleware::Error::Reqwest(e) => classify_error(url, &e),
+        reqwest_middleware::Error::Middleware(e) => match e.downcast_ref::<RetryError>() {
+            Some(RetryError::WithRetries {
+                err: reqwest_middleware::Error::Reqwest(inner),
+                ..
+            })
+            | Some(RetryError::Error(reqwest_middleware::Error::Reqwest(inner))) => {
+                classify_error(url, inner)
+            }
+            _ => AppError::NetworkRequestFailed(format!("{url}: {e:#}")),
+        },
+    }
+}
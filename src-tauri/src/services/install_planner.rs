@@ -0,0 +1,570 @@
+use crate::models::config::DownloadSettings;
+use crate::models::downloader::{
+    AssetIndex, AssetObjects, DownloadDetail, DownloadTask, Library, LibraryArtifact, Logging,
+    MinecraftManifestVersion, VersionLoader,
+};
+use crate::models::error::AppError;
+use crate::models::fabric::FabricInstaller;
+use crate::models::install_plan::{InstallPlan, PlannedFile, PlannedFileKind};
+use crate::models::java::Java;
+use crate::models::launch_context::LaunchContext;
+use crate::models::utils::LowerCaseStartsWith;
+use crate::models::versions::{MinecraftVersion, VersionBase};
+use crate::services::directory_manager::{
+    get_assets_directory, get_java_dir, get_libraries_directory, get_natives_directory,
+    get_version_directory, get_version_manifest, get_versions_directory,
+};
+use crate::services::game_downloader::{neoforge_installer_url, neoforge_minecraft_version, quilt_profile_url};
+use crate::services::http_client::{classify_error, HttpClient};
+use crate::services::liteloader::liteloader_version_json;
+use crate::services::jdk_manager::{resolve_java_files, resolve_java_files_local};
+use crate::services::metadata_cache::{get_cached_entry, get_cached_json, get_cached_text};
+use crate::services::mirror_manager::{get_json_from_mirrors, get_text_from_mirrors};
+use crate::services::rule_engine::is_allowed;
+use crate::services::utils::{
+    arm64_natives_path, convert_to_full_path, convert_to_full_url, fetch_library_path, MAVEN_CENTRAL,
+    fetch_unofficial_library_repos, verify_file_existence_with_sha,
+};
+use crate::services::version_manager::load_version_manifest;
+use log::info;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Resolves everything the installation of the given loader would fetch, without downloading or writing anything.
+///
+/// Files that only come to exist once a loader's installer has run (e.g. Forge's patched libraries) can't be
+/// resolved ahead of time, the plan mentions them in its notes instead.
+pub async fn plan_install(
+    client: &HttpClient,
+    version_loader: &VersionLoader,
+    settings: &DownloadSettings,
+) -> Result<InstallPlan, AppError> {
+    let version_id = version_loader.get_installed_id();
+    let mut plan = InstallPlan::new(version_id.clone());
+    match version_loader.base {
+        VersionBase::VANILLA => {
+            let json = fetch_vanilla_json(client, &version_id, settings, &mut plan).await?;
+            plan.extend(plan_version(client, &json, settings).await?);
+        }
+        VersionBase::FABRIC => {
+            let mc_version = version_loader.get_fabric_version_id();
+            plan_fabric_installer(client, version_loader, settings, &mut plan).await?;
+
+            let url = format!(
+                "https://meta.fabricmc.net/v2/versions/loader/{mc_version}/{}/profile/json",
+                version_loader.get_fabric_loader_id()
+            );
+            let profile: MinecraftManifestVersion = get_json_from_mirrors(client, &url, &settings.mirrors).await?;
+            plan.extend(plan_version(client, &profile, settings).await?);
+
+            let mut vanilla = InstallPlan::new(mc_version.clone());
+            let json = fetch_vanilla_json(client, &mc_version, settings, &mut vanilla).await?;
+            vanilla.extend(plan_version(client, &json, settings).await?);
+            plan.extend(vanilla);
+        }
+        VersionBase::QUILT => {
+            let mc_version = version_loader.get_quilt_version_id();
+            let url = quilt_profile_url(version_loader);
+            plan.push(PlannedFileKind::VersionJson, DownloadTask::new(url.clone(), get_version_manifest(&version_id), "", 0));
+            let profile: MinecraftManifestVersion = get_json_from_mirrors(client, &url, &settings.mirrors).await?;
+            plan.extend(plan_version(client, &profile, settings).await?);
+
+            let mut vanilla = InstallPlan::new(mc_version.clone());
+            let json = fetch_vanilla_json(client, &mc_version, settings, &mut vanilla).await?;
+            vanilla.extend(plan_version(client, &json, settings).await?);
+            plan.extend(vanilla);
+        }
+        VersionBase::LITELOADER => {
+            let mc_version = version_loader.id.split("-LiteLoader").next().unwrap_or_default().to_string();
+            let mut vanilla = InstallPlan::new(mc_version.clone());
+            let json = fetch_vanilla_json(client, &mc_version, settings, &mut vanilla).await?;
+
+            // Only the libraries matter here, the launch arguments are taken from the vanilla JSON on install.
+            let profile = liteloader_version_json(client, version_loader, "", settings).await?;
+            let profile: MinecraftManifestVersion =
+                serde_json::from_value(profile).map_err(|e| AppError::JsonParseFailed(e.to_string()))?;
+            plan.extend(plan_version(client, &profile, settings).await?);
+            plan.notes.push(format!("The {version_id} profile is generated from LiteLoader's versions.json."));
+
+            vanilla.extend(plan_version(client, &json, settings).await?);
+            plan.extend(vanilla);
+        }
+        VersionBase::FORGE | VersionBase::NEOFORGE => {
+            let version = &version_loader.id;
+            let (url, path, mc_version) = if version_loader.base == VersionBase::FORGE {
+                let url = format!("https://maven.minecraftforge.net/net/minecraftforge/forge/{version}/forge-{version}-installer.jar");
+                let path = client.temp_directory().join(format!("forge-{version}-installer.jar"));
+                (url, path, version.split("-").next().unwrap_or(version).to_string())
+            } else {
+                let path = client.temp_directory().join(format!("neoforge-{version}-installer.jar"));
+                let mc_version = neoforge_minecraft_version(version)
+                    .ok_or_else(|| AppError::ManifestParseFailed(format!("Invalid NeoForge version {version}")))?;
+                (neoforge_installer_url(version), path, mc_version)
+            };
+            plan.push(PlannedFileKind::Installer, DownloadTask::new(url, path, "", 0));
+
+            let installed_json = get_version_manifest(&version_id);
+            if let Ok(json) = read_version_json(&installed_json) {
+                plan.extend(plan_version(client, &json, settings).await?);
+            } else {
+                plan.notes.push(format!(
+                    "The libraries of {version_id} are resolved by its installer and aren't part of this plan."
+                ));
+            }
+
+            let mut vanilla = InstallPlan::new(mc_version.clone());
+            let json = fetch_vanilla_json(client, &mc_version, settings, &mut vanilla).await?;
+            vanilla.extend(plan_version(client, &json, settings).await?);
+            plan.extend(vanilla);
+        }
+    }
+    plan.check_installed();
+    info!(
+        "Planned {} files for {version_id}, {} of {} bytes left to download",
+        plan.files.len(),
+        plan.download_bytes,
+        plan.total_bytes
+    );
+    Ok(plan)
+}
+
+/// Resolves the files of a single version JSON: its Java runtime, libraries, natives, client jar, assets and
+/// logging config.
+pub async fn plan_version(
+    client: &HttpClient,
+    json: &MinecraftManifestVersion,
+    settings: &DownloadSettings,
+) -> Result<InstallPlan, AppError> {
+    resolve_version(Some((client, settings)), json).await
+}
+
+/// Resolves the files launching an installed version relies on from what's on the disk alone, the version it
+/// inherits from included. nothing gets fetched, so files whose details only the network knows (e.g. the files of
+/// a Java runtime whose manifest was never cached) are only checked for existence. the files themselves aren't
+/// hashed, that's left to `InstallPlan::check_installed`. a version JSON that's missing or can't be parsed, the
+/// inherited one's included, fails the plan.
+pub async fn plan_offline_launch(version: &MinecraftVersion) -> Result<InstallPlan, AppError> {
+    let mut plan = InstallPlan::new(version.id.clone());
+    let inherited = version.get_inherited();
+    let mut versions = vec![version.clone()];
+    if inherited != *version {
+        versions.push(inherited.clone());
+    }
+
+    for version in versions {
+        let path = PathBuf::from(version.get_json());
+        plan.push(PlannedFileKind::VersionJson, DownloadTask::new(String::new(), path.clone(), "", 0));
+        // Leaving out a version that can't be read would make its files look unused, so it fails the whole plan.
+        let json = read_version_json(&path)?;
+        plan.extend(resolve_version(None, &json).await?);
+    }
+
+    // Natives are launched from where they were extracted to, not from their jars.
+    if plan.files.iter().any(|x| x.kind == PlannedFileKind::Native) {
+        let natives = get_natives_directory(&inherited.id);
+        plan.push(PlannedFileKind::Native, DownloadTask::new(String::new(), natives, "", 0));
+    }
+    Ok(plan)
+}
+
+/// The version JSON that keeps `plan_offline_launch` from resolving `version`, if any.
+pub fn unreadable_version_json(version: &MinecraftVersion) -> Option<PlannedFile> {
+    [version.clone(), version.get_inherited()]
+        .into_iter()
+        .map(|x| PathBuf::from(x.get_json()))
+        .find(|x| read_version_json(x).is_err())
+        .map(|x| PlannedFile::new(PlannedFileKind::VersionJson, DownloadTask::new(String::new(), x, "", 0)))
+}
+
+/// Where the planner may look files up, `None` keeps it to what's already on the disk.
+type Network<'a> = Option<(&'a HttpClient, &'a DownloadSettings)>;
+
+async fn resolve_version(network: Network<'_>, json: &MinecraftManifestVersion) -> Result<InstallPlan, AppError> {
+    let mut plan = InstallPlan::new(json.id.clone());
+
+    if let Some(java_version) = &json.java_version {
+        let major_version = java_version.major_version.to_string();
+        plan_runtime(network, &java_version.component, &major_version, &mut plan).await?;
+    } else if let Some(inherits) = &json.inherits_from {
+        match read_version_json(&get_version_manifest(inherits)) {
+            Ok(inherited) => {
+                let java_version = inherited.java_version.ok_or_else(|| {
+                    AppError::ManifestParseFailed("Java version missing in inherited manifest".to_string())
+                })?;
+                let major_version = java_version.major_version.to_string();
+                plan_runtime(network, &java_version.component, &major_version, &mut plan).await?;
+            }
+            // Whoever plans the inherited version takes care of its runtime.
+            Err(_) => info!("{inherits} isn't installed yet, leaving its Java runtime to its own plan"),
+        }
+    } else if network.is_none() {
+        // Launches take the runtime from the version this one is based on, which gets resolved on its own.
+        info!("{} doesn't name its Java runtime, leaving it to the version it's based on", json.id);
+    } else {
+        return Err(AppError::ManifestParseFailed("No Java version found in manifest".to_string()));
+    }
+
+    plan_libraries(network, &json.libraries, &mut plan).await?;
+
+    if let Some(downloads) = &json.downloads {
+        if let Some(client_download) = downloads.get("client") {
+            plan_client(client_download, &json.id, &mut plan);
+        }
+    }
+
+    if let Some(asset_index) = &json.asset_index {
+        plan_assets(network, asset_index, &mut plan).await?;
+    }
+
+    if let Some(logging) = &json.logging {
+        plan_logging(logging, &json.id, &mut plan);
+    }
+    Ok(plan)
+}
+
+async fn plan_runtime(
+    network: Network<'_>,
+    component: &str,
+    version: &str,
+    plan: &mut InstallPlan,
+) -> Result<(), AppError> {
+    let Some((client, settings)) = network else {
+        // The runtime's file list is only known if its manifests were cached, otherwise its release file and
+        // binary have to do.
+        let runtime_dir = get_java_dir().join(component);
+        for task in resolve_java_files_local(&component.to_string(), &version.to_string()).unwrap_or_default() {
+            plan.push(PlannedFileKind::JavaRuntime, task);
+        }
+        plan.push(PlannedFileKind::JavaRuntime, DownloadTask::new(String::new(), runtime_dir.join("release"), "", 0));
+        plan.push(PlannedFileKind::JavaRuntime, DownloadTask::new(String::new(), Java::bin_file_of(&runtime_dir), "", 0));
+        return Ok(());
+    };
+    let tasks = resolve_java_files(client, &component.to_string(), &version.to_string(), settings).await?;
+    for task in tasks {
+        plan.push(PlannedFileKind::JavaRuntime, task);
+    }
+    plan.add_runtime(component, version);
+    Ok(())
+}
+
+async fn plan_libraries(network: Network<'_>, libraries: &[Library], plan: &mut InstallPlan) -> Result<(), AppError> {
+    let client = network.map(|(client, _)| client);
+    let libraries_path = get_libraries_directory();
+    let context = LaunchContext::current();
+
+    for library in libraries {
+        if !is_allowed(library.rules.as_deref(), &context) {
+            continue;
+        }
+        if library.downloads.is_none() {
+            if let Some(url) = &library.url {
+                let full_url = convert_to_full_url(url.to_string(), library.name.to_string())?;
+                let full_path = convert_to_full_path(libraries_path.to_string_lossy().into_owned(), &library.name)?;
+                let hash = library.sha1.clone().unwrap_or_default();
+                plan.push(
+                    PlannedFileKind::Library,
+                    DownloadTask::new(full_url, PathBuf::from(full_path), &hash, library.size.unwrap_or(0)),
+                );
+                continue;
+            }
+            let name = library.name.replace(":", "/");
+            let path = fetch_library_path(&name)?;
+            let full_path = libraries_path.join(&path);
+            if name.starts_with_lower_case("net/minecraft") {
+                let url = format!("https://libraries.minecraft.net/{path}");
+                plan.push(PlannedFileKind::Library, DownloadTask::new(url, full_path, "", 0));
+                continue;
+            }
+            let urls = fetch_unofficial_library_repos(&path);
+            let Some(client) = client.filter(|_| !full_path.exists()) else {
+                // Either already there or there's no way to look for which repo has it.
+                if let Some(url) = urls.into_iter().next() {
+                    plan.push(PlannedFileKind::Library, DownloadTask::new(url, full_path, "", 0));
+                }
+                continue;
+            };
+            for url in urls {
+                if client
+                    .without_retries()
+                    .head(url.clone())
+                    .timeout(Duration::from_secs(3))
+                    .send()
+                    .await
+                    .map_err(|x| classify_error(&url, &x))?
+                    .status()
+                    .is_success()
+                {
+                    plan.push(PlannedFileKind::Library, DownloadTask::new(url, full_path, "", 0));
+                    break;
+                }
+            }
+            continue;
+        }
+
+        let downloads = library
+            .downloads
+            .as_ref()
+            .ok_or_else(|| AppError::ManifestParseFailed("Downloads missing".to_string()))?;
+        if let Some(native) = native_task(network, library, downloads.classifiers.as_ref(), &context).await? {
+            let exclude = library.extract.clone().unwrap_or_default().exclude;
+            plan.push_native(native, exclude);
+        }
+        let Some(library_artifact) = &downloads.artifact else {
+            continue;
+        };
+
+        let library_path = if let Some(path) = &library_artifact.path {
+            path.to_string()
+        } else {
+            let args = library.name.split(":").collect::<Vec<&str>>();
+            if args.len() < 3 {
+                return Err(AppError::ManifestParseFailed(format!("Invalid library name: {}", library.name)));
+            }
+            let group_id = args[0].replace(".", "/");
+            let artifact = args[1];
+            let version = args[2];
+            format!("{group_id}/{artifact}/{version}/{artifact}-{version}.jar")
+        };
+
+        let path = libraries_path.join(library_path.as_str());
+        let hash = library_artifact.sha1.clone().unwrap_or_default();
+        let task = DownloadTask::new(
+            library_artifact.url.clone(),
+            path,
+            hash.as_str(),
+            library_artifact.size.unwrap_or(0),
+        );
+        // Since 1.19 the natives are regular artifacts, e.g. `org.lwjgl:lwjgl:3.3.1:natives-linux-arm64`.
+        match library.name.split(':').nth(3).filter(|x| x.starts_with("natives-")) {
+            Some(classifier) if !natives_match_arch(classifier, &context.arch) => {
+                if let Some(path) = arm64_natives_path(&library.name, classifier, &context) {
+                    let task = maven_central_task(network, path).await?;
+                    plan.push_native(task, vec!["META-INF/".to_string()]);
+                }
+            }
+            Some(_) => plan.push_native(task, vec!["META-INF/".to_string()]),
+            None => plan.push(PlannedFileKind::Library, task),
+        }
+    }
+    Ok(())
+}
+
+/// Whether a `natives-<os>[-<arch>]` classifier is built for the given architecture, the ones without an
+/// architecture are the x86_64 builds.
+fn natives_match_arch(classifier: &str, arch: &str) -> bool {
+    let classifier_arch = ["arm64", "aarch_64", "x86", "arm32"]
+        .into_iter()
+        .find(|x| classifier.ends_with(&format!("-{x}")))
+        .map(|x| if x == "aarch_64" { "arm64" } else { x });
+    classifier_arch.unwrap_or("x86_64") == arch
+}
+
+/// Picks the natives classifier of the current os and returns the task needed to download it. the library's
+/// `natives` map names the classifier when it has one, otherwise the usual names are tried.
+async fn native_task(
+    network: Network<'_>,
+    library: &Library,
+    classifiers: Option<&HashMap<String, LibraryArtifact>>,
+    context: &LaunchContext,
+) -> Result<Option<DownloadTask>, AppError> {
+    let Some(classifiers_map) = classifiers else {
+        return Ok(None);
+    };
+
+    let os = &context.os;
+    let classifier = match &library.natives {
+        Some(natives) => {
+            let bits = if context.arch == "x86" { "32" } else { "64" };
+            let Some(classifier) = natives.get(os) else {
+                return Ok(None);
+            };
+            classifier.replace("${arch}", bits)
+        }
+        None if os == "windows" && !classifiers_map.contains_key("natives-windows") => "natives-windows-64".to_string(),
+        None => format!("natives-{os}"),
+    };
+    if let Some(path) = arm64_natives_path(&library.name, &classifier, context) {
+        return maven_central_task(network, path).await.map(Some);
+    }
+
+    let Some(val) = classifiers_map.get(&classifier) else {
+        return Ok(None);
+    };
+    let url = val.url.to_string();
+    let url_https_less = url.replace("https://", "").replace("http://", "");
+    let path = if let Some(p) = &val.path {
+        p.to_string()
+    } else {
+        let url_args = url_https_less.split("/").collect::<Vec<&str>>();
+        url_https_less.replace(url_args[0], "")
+    };
+
+    let full_path = get_libraries_directory().join(path);
+    let size = val.size.unwrap_or(0);
+    let hash = val.sha1.clone().unwrap_or_default();
+    Ok(Some(DownloadTask::new(url, full_path, hash.as_str(), size)))
+}
+
+/// An artifact of Maven Central, verified against the `.sha1` file published next to it. offline the hash fetched
+/// last time is used, if there's none the file is taken as is.
+async fn maven_central_task(network: Network<'_>, path: String) -> Result<DownloadTask, AppError> {
+    let url = format!("{MAVEN_CENTRAL}{path}");
+    let sha1_url = format!("{url}.sha1");
+    let sha1 = match network {
+        Some((client, settings)) => get_cached_text(client, &sha1_url, settings).await?,
+        None => get_cached_entry(&sha1_url).map(|x| x.body).unwrap_or_default(),
+    };
+    // The file holds the hash, sometimes followed by the artifact's name.
+    let sha1 = sha1.split_whitespace().next().unwrap_or_default().to_lowercase();
+    Ok(DownloadTask::new(url, get_libraries_directory().join(path), &sha1, 0))
+}
+
+fn plan_client(value: &DownloadDetail, version: &String, plan: &mut InstallPlan) {
+    let path = get_versions_directory()
+        .join(version)
+        .join(format!("{}.jar", version));
+    plan.push(PlannedFileKind::Client, DownloadTask::new(value.url.clone(), path, &value.sha1, value.size));
+}
+
+async fn plan_assets(network: Network<'_>, value: &AssetIndex, plan: &mut InstallPlan) -> Result<(), AppError> {
+    let id = &value.id;
+    let url = value.url.clone();
+    let asset_index_path = get_assets_directory().join("indexes").join(format!("{id}.json"));
+
+    // The index has to be read to know the objects, it's only fetched into memory if it isn't installed yet.
+    let content = if verify_file_existence_with_sha(&asset_index_path, &value.sha1).unwrap_or(false) {
+        fs::read_to_string(&asset_index_path)
+            .map_err(|e| AppError::FileReadFailed(format!("Failed to read asset index: {}", e)))?
+    } else if let Some((client, settings)) = network {
+        get_text_from_mirrors(client, &url, &settings.mirrors).await?
+    } else {
+        // Without the index its objects can't be known, the missing index is what gets reported.
+        plan.push(
+            PlannedFileKind::AssetIndex,
+            DownloadTask::new(url, asset_index_path, &value.sha1, value.size),
+        );
+        return Ok(());
+    };
+    plan.push(
+        PlannedFileKind::AssetIndex,
+        DownloadTask::new(url, asset_index_path, &value.sha1, value.size),
+    );
+
+    let json: AssetObjects = serde_json::from_str(content.as_str())
+        .map_err(|e| AppError::JsonParseFailed(format!("Asset index isn't well formatted: {}", e)))?;
+
+    let url_template = "https://resources.download.minecraft.net/{id}/{hash}";
+    for asset_entry in json.objects.values() {
+        let hash = &asset_entry.hash;
+        let prefix_id = hash[0..2].to_string();
+        let url = url_template
+            .replace("{id}", prefix_id.as_str())
+            .replace("{hash}", hash);
+        let path = get_assets_directory()
+            .join("objects")
+            .join(prefix_id.as_str())
+            .join(hash);
+        plan.push(PlannedFileKind::Asset, DownloadTask::new(url, path, hash.as_str(), asset_entry.size));
+    }
+    Ok(())
+}
+
+fn plan_logging(logging: &Logging, version: &String, plan: &mut InstallPlan) {
+    let file = &logging.client.file;
+    let filename = file.url.split("/").last().unwrap_or("logging.xml");
+    plan.push(
+        PlannedFileKind::Logging,
+        DownloadTask::new(
+            file.url.clone(),
+            get_version_directory(version).join(filename),
+            file.sha1.as_str(),
+            file.size,
+        ),
+    );
+}
+
+/// Reads the vanilla version JSON from the disk, or from the version manifest if it isn't installed yet.
+async fn fetch_vanilla_json(
+    client: &HttpClient,
+    id: &String,
+    settings: &DownloadSettings,
+    plan: &mut InstallPlan,
+) -> Result<MinecraftManifestVersion, AppError> {
+    let mirrors = &settings.mirrors;
+    let manifest = load_version_manifest(client, settings).await?;
+    let version = manifest
+        .versions
+        .iter()
+        .find(|v| &v.id == id)
+        .ok_or_else(|| AppError::ManifestParseFailed(format!("Couldn't find version in manifest. {id}")))?;
+    let path = get_version_manifest(id);
+    plan.push(PlannedFileKind::VersionJson, DownloadTask::new(version.url.clone(), path.clone(), "", 0));
+
+    if let Ok(json) = read_version_json(&path) {
+        return Ok(json);
+    }
+    get_json_from_mirrors(client, &version.url, mirrors).await
+}
+
+async fn plan_fabric_installer(
+    client: &HttpClient,
+    version_loader: &VersionLoader,
+    settings: &DownloadSettings,
+    plan: &mut InstallPlan,
+) -> Result<(), AppError> {
+    let installers_url = "https://meta.fabricmc.net/v2/versions/installer";
+    let installers = get_cached_json::<Vec<FabricInstaller>>(client, installers_url, settings).await?;
+    let stable_installer = installers
+        .iter()
+        .find(|x| x.stable)
+        .ok_or_else(|| AppError::VersionNotFound)?;
+    let installer_path = convert_to_full_path(
+        client.temp_directory().to_string_lossy().into_owned(),
+        &stable_installer.maven,
+    )?;
+    plan.push(
+        PlannedFileKind::Installer,
+        DownloadTask::new(stable_installer.url.to_string(), PathBuf::from(installer_path), "", 0),
+    );
+    plan_runtime(Some((client, settings)), "jre-legacy", "8", plan).await?;
+    plan.notes.push(format!(
+        "The {} profile is written by the Fabric installer.",
+        version_loader.get_installed_id()
+    ));
+    Ok(())
+}
+
+fn read_version_json(path: &PathBuf) -> Result<MinecraftManifestVersion, AppError> {
+    let content =
+        fs::read_to_string(path).map_err(|e| AppError::FileReadFailed(format!("{}: {e}", path.display())))?;
+    serde_json::from_str(&content).map_err(|e| AppError::JsonParseFailed(format!("{}: {e}", path.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifiers_without_an_arch_are_x86_64() {
+        assert!(natives_match_arch("natives-linux", "x86_64"));
+        assert!(natives_match_arch("natives-windows", "x86_64"));
+        assert!(!natives_match_arch("natives-linux", "arm64"));
+    }
+
+    #[test]
+    fn classifiers_name_their_arch() {
+        assert!(natives_match_arch("natives-linux-arm64", "arm64"));
+        assert!(natives_match_arch("natives-macos-arm64", "arm64"));
+        assert!(natives_match_arch("natives-windows-x86", "x86"));
+        assert!(natives_match_arch("natives-linux-arm32", "arm32"));
+        assert!(!natives_match_arch("natives-windows-arm64", "x86_64"));
+    }
+
+    #[test]
+    fn aarch_64_is_arm64() {
+        assert!(natives_match_arch("natives-osx-aarch_64", "arm64"));
+        assert!(!natives_match_arch("natives-osx-aarch_64", "x86_64"));
+    }
+}
@@ -1,16 +1,25 @@
-use crate::models::error::AppError;
+use crate::models::config::DownloadSettings;
+use crate::models::downloader::DownloadTask;
+use crate::models::error::{AppError, Void};
 use crate::models::java::Java;
 use crate::models::logger::LogLine;
-use crate::models::mirror::Mirror;
-use crate::models::platform::get_current_os_with_architecture;
+use crate::models::platform::{get_current_arch, get_current_os, get_current_os_with_architecture};
 use crate::services::directory_manager::get_java_dir;
-use crate::services::game_downloader::download_file_if_not_exists;
-use crate::services::utils::load_json_url;
+use crate::services::download_pool::download_all;
+use crate::services::http_client::HttpClient;
+use crate::services::metadata_cache::{get_cached_entry, get_cached_json};
+use crate::services::utils::remove_if_exists;
+use flate2::read::GzDecoder;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::fs::create_dir_all;
+use std::fs::{create_dir_all, File};
+use std::io;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
 use tokio::sync::mpsc::UnboundedSender;
 use log::info;
+use zip::ZipArchive;
 
 pub fn get_java(java: String) -> Result<Java, AppError> {
     let runtime_dir = get_java_dir().join(&java);
@@ -18,31 +27,106 @@ pub fn get_java(java: String) -> Result<Java, AppError> {
 }
 
 pub async fn download_java(
+    client: &HttpClient,
     java: &String,
     version: &String,
     _logger: &UnboundedSender<LogLine>,
-    mirror: &Mirror,
+    settings: &DownloadSettings,
 ) -> Result<(), AppError> {
-    let runtime_dir = get_java_dir().join(&java);
+    let tasks = resolve_java_files(client, java, version, settings).await?;
+    info!("Downloading {} files of the {java} runtime", tasks.len());
+    download_all(client, tasks, settings, None).await?;
+    install_runtime_archive(client, java, version, settings).await?;
+    mark_runtime_executables(java, version)?;
+    write_java_release(java, version)
+}
+
+const ADOPTIUM_ASSETS_URL: &str = "https://api.adoptium.net/v3/assets/latest";
 
-    if !mirror.is_connected().await {
-        return Err(AppError::MirrorConnectionFailed("Mirror is not connected to download".to_string()));
+const JAVA_RUNTIMES_URL: &str =
+    "https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+
+/// Resolves every file of the given Java runtime component for the current os, without downloading any of them.
+///
+/// Both manifests go through the metadata cache, which is what lets `resolve_java_files_local` list the files
+/// later on without the network. when Mojang has no build for the current os and architecture (e.g. arm64 Linux)
+/// an Adoptium JRE of the same major version is resolved instead, as a single archive.
+pub async fn resolve_java_files(
+    client: &HttpClient,
+    java: &String,
+    version: &String,
+    settings: &DownloadSettings,
+) -> Result<Vec<DownloadTask>, AppError> {
+    let runtimes: Value = get_cached_json(client, JAVA_RUNTIMES_URL, settings).await?;
+    let Some(manifest_url) = find_runtime_manifest_url(&runtimes, java, version)? else {
+        info!("Mojang has no {java} runtime for {}, falling back to Adoptium", get_current_os_with_architecture());
+        return resolve_fallback_archive(client, java, version, settings).await;
+    };
+    let runtime_manifest: Value = get_cached_json(client, &manifest_url, settings).await?;
+    runtime_files(&runtime_manifest, java)
+}
+
+/// The files of the given Java runtime component as of the last time its manifests were fetched, `None` when they
+/// never were or can't be read anymore.
+pub fn resolve_java_files_local(java: &String, version: &String) -> Option<Vec<DownloadTask>> {
+    runtime_files(&cached_runtime_manifest(java, version)?, java).ok()
+}
+
+fn cached_runtime_manifest(java: &String, version: &String) -> Option<Value> {
+    let runtimes: Value = serde_json::from_str(&get_cached_entry(JAVA_RUNTIMES_URL)?.body).ok()?;
+    let manifest_url = find_runtime_manifest_url(&runtimes, java, version).ok()??;
+    serde_json::from_str(&get_cached_entry(&manifest_url)?.body).ok()
+}
+
+/// Gives the files Mojang's runtime manifest marks as executable (`java`, `jspawnhelper`...) the permission to be
+/// run. they're the only downloaded files needing it, and as stored objects are shared no other file gets touched.
+#[cfg(unix)]
+pub fn mark_runtime_executables(java: &String, version: &String) -> Void {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Adoptium archives keep the permissions of their entries, they have no manifest to go through.
+    let Some(runtime_manifest) = cached_runtime_manifest(java, version) else {
+        return Ok(());
+    };
+    let runtime_dir = get_java_dir().join(java);
+    let files = runtime_manifest.get("files").and_then(|x| x.as_object()).into_iter().flatten();
+    for (name, file) in files {
+        if !file.get("executable").and_then(|x| x.as_bool()).unwrap_or(false) {
+            continue;
+        }
+        let path = runtime_dir.join(name);
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
+        let mut permissions = metadata.permissions();
+        if permissions.mode() & 0o111 != 0o111 {
+            permissions.set_mode(permissions.mode() | 0o111);
+            fs::set_permissions(&path, permissions)
+                .map_err(|e| AppError::AccessDenied(format!("{}: {e}", path.display())))?;
+        }
     }
+    Ok(())
+}
 
-    let url = mirror.parse_url(&"https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json".to_string());
-    let current_os = get_current_os_with_architecture();
+/// Files can be run no matter their permissions on this platform.
+#[cfg(not(unix))]
+pub fn mark_runtime_executables(_java: &String, _version: &String) -> Void {
+    Ok(())
+}
 
-    let json: Value = load_json_url(&url.to_string())
-        .await
-        .ok_or_else(|| AppError::NetworkRequestFailed("Couldn't get or read the runtime json manifest file.".to_string()))?;
+/// The url of the runtime's manifest, `None` when Mojang has no build of it for the current os and architecture.
+fn find_runtime_manifest_url(runtimes: &Value, java: &String, version: &String) -> Result<Option<String>, AppError> {
+    let current_os = get_current_os_with_architecture();
 
-    let runtime_arr = json
+    let Some(runtime_arr) = runtimes
         .get(&current_os)
         .and_then(|os_data| os_data.get(java))
         .and_then(|java_data| java_data.as_array())
-        .ok_or_else(|| AppError::ManifestParseFailed(format!("Missing Java runtime data for OS {} and component {}", current_os, java)))?;
+    else {
+        return Ok(None);
+    };
 
-    let runtime_v = runtime_arr
+    let Some(runtime_v) = runtime_arr
         .iter()
         .find(|x| {
             x.pointer("/version/name")
@@ -51,27 +135,194 @@ pub async fn download_java(
                 .unwrap_or(false)
         })
         .or_else(|| runtime_arr.first())
-        .ok_or_else(|| AppError::ManifestParseFailed("Java runtime array is empty".to_string()))?;
+    else {
+        return Ok(None);
+    };
 
-    let runtime_manifest_url_str = runtime_v
+    runtime_v
         .pointer("/manifest/url")
         .and_then(|u| u.as_str())
-        .ok_or_else(|| AppError::ManifestParseFailed("Missing manifest URL for Java runtime".to_string()))?;
+        .map(|u| Some(u.to_string()))
+        .ok_or_else(|| AppError::ManifestParseFailed("Missing manifest URL for Java runtime".to_string()))
+}
+
+/// Resolves the archive of an Adoptium JRE of the given major version, nothing when it's already unpacked.
+async fn resolve_fallback_archive(
+    client: &HttpClient,
+    java: &String,
+    version: &String,
+    settings: &DownloadSettings,
+) -> Result<Vec<DownloadTask>, AppError> {
+    let runtime_dir = get_java_dir().join(java);
+    if runtime_dir.join("release").exists() && Java::bin_file_of(&runtime_dir).exists() {
+        return Ok(Vec::new());
+    }
+
+    let package = adoptium_package(client, version, settings).await?;
+    let link = package
+        .get("link")
+        .and_then(|x| x.as_str())
+        .ok_or_else(|| AppError::ManifestParseFailed("Missing link of the Adoptium package".to_string()))?;
+    let size = package.get("size").and_then(|x| x.as_u64()).unwrap_or(0);
+    let extension = if link.ends_with(".zip") { "zip" } else { "tar.gz" };
+    Ok(vec![DownloadTask::new(link.to_string(), runtime_archive(client, java, extension), "", size)])
+}
+
+/// The latest Adoptium JRE package of the given Java version for the current platform.
+async fn adoptium_package(client: &HttpClient, version: &String, settings: &DownloadSettings) -> Result<Value, AppError> {
+    let (os, arch) = adoptium_platform();
+    let url = format!(
+        "{ADOPTIUM_ASSETS_URL}/{version}/hotspot?architecture={arch}&image_type=jre&os={os}&vendor=eclipse"
+    );
+    let assets: Value = get_cached_json(client, &url, settings).await?;
+    assets
+        .as_array()
+        .and_then(|x| x.first())
+        .and_then(|x| x.pointer("/binary/package"))
+        .cloned()
+        .ok_or_else(|| {
+            AppError::ManifestParseFailed(format!("Neither Mojang nor Adoptium has a Java {version} runtime for {os} {arch}"))
+        })
+}
 
-    let runtime_manifest_url = mirror.parse_url(&runtime_manifest_url_str.to_string());
+/// Adoptium's names of the current os and architecture.
+fn adoptium_platform() -> (&'static str, String) {
+    let os = match get_current_os().as_str() {
+        "windows" => "windows",
+        "osx" => "mac",
+        _ => "linux",
+    };
+    let arch = match get_current_arch().as_str() {
+        "x86_64" => "x64".to_string(),
+        "arm64" => "aarch64".to_string(),
+        "arm32" => "arm".to_string(),
+        arch => arch.to_string(),
+    };
+    (os, arch)
+}
 
-    let runtime_manifest: Value = reqwest::get(runtime_manifest_url)
-        .await
-        .map_err(|e| AppError::NetworkRequestFailed(format!("Couldn't get runtime manifest: {}", e)))?
-        .json()
-        .await
-        .map_err(|e| AppError::JsonParseFailed(format!("Failed to read the runtime json file: {}", e)))?;
+/// Where the archive of a fallback runtime is downloaded to before it's unpacked.
+fn runtime_archive(client: &HttpClient, java: &String, extension: &str) -> PathBuf {
+    client.temp_directory().join(format!("{java}.{extension}"))
+}
+
+/// Unpacks the downloaded archive of a fallback runtime into the runtime's directory, if there is one.
+pub async fn install_runtime_archive(
+    client: &HttpClient,
+    java: &String,
+    version: &String,
+    settings: &DownloadSettings,
+) -> Void {
+    let runtime_dir = get_java_dir().join(java);
+    for extension in ["tar.gz", "zip"] {
+        let archive = runtime_archive(client, java, extension);
+        if !archive.exists() {
+            continue;
+        }
+        // Adoptium only publishes a SHA-256 of its archives, which the download itself can't check.
+        let package = adoptium_package(client, version, settings).await?;
+        let checksum = package
+            .get("checksum")
+            .and_then(|x| x.as_str())
+            .ok_or_else(|| AppError::ManifestParseFailed("Missing checksum of the Adoptium package".to_string()))?;
+        if !sha256_matches(&archive, checksum)? {
+            remove_if_exists(&archive)?;
+            return Err(AppError::FileVerificationFailed(format!(
+                "{} doesn't match its SHA-256 {checksum}",
+                archive.display()
+            )));
+        }
+        info!("Unpacking {} into {}", archive.display(), runtime_dir.display());
+        if runtime_dir.exists() {
+            fs::remove_dir_all(&runtime_dir).map_err(|e| AppError::FileDeleteFailed(e.to_string()))?;
+        }
+        create_dir_all(&runtime_dir).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+        if extension == "zip" {
+            unpack_zip(&archive, &runtime_dir)?;
+        } else {
+            unpack_tar_gz(&archive, &runtime_dir)?;
+        }
+        fs::remove_file(&archive).map_err(|e| AppError::FileDeleteFailed(e.to_string()))?;
+    }
+    Ok(())
+}
+
+fn sha256_matches(path: &Path, expected: &str) -> Result<bool, AppError> {
+    let mut file = File::open(path).map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 8192];
+    loop {
+        let bytes_read = file.read(&mut buffer).map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+    let hash = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    Ok(hash.eq_ignore_ascii_case(expected))
+}
+
+fn unpack_tar_gz(archive: &Path, runtime_dir: &Path) -> Void {
+    let file = File::open(archive).map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+    let mut tar = tar::Archive::new(GzDecoder::new(file));
+    let entries = tar.entries().map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+        let name = entry.path().map_err(|e| AppError::ZipParseFailed(e.to_string()))?.into_owned();
+        let Some(path) = runtime_entry_path(&name) else {
+            continue;
+        };
+        let dest = runtime_dir.join(path);
+        if let Some(parent) = dest.parent() {
+            create_dir_all(parent).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+        }
+        entry
+            .unpack(&dest)
+            .map_err(|e| AppError::ZipExtractionFailed(format!("{}: {e}", name.display())))?;
+    }
+    Ok(())
+}
+
+fn unpack_zip(archive: &Path, runtime_dir: &Path) -> Void {
+    let file = File::open(archive).map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+        let Some(path) = entry.enclosed_name().filter(|_| !entry.is_dir()).and_then(|x| runtime_entry_path(&x)) else {
+            continue;
+        };
+        let dest = runtime_dir.join(path);
+        if let Some(parent) = dest.parent() {
+            create_dir_all(parent).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+        }
+        let mut out = File::create(&dest).map_err(|e| AppError::FileCreateFailed(e.to_string()))?;
+        io::copy(&mut entry, &mut out).map_err(|e| AppError::ZipExtractionFailed(format!("{}: {e}", entry.name())))?;
+    }
+    Ok(())
+}
+
+/// The path of an archive entry inside the runtime, without the archive's top directory (and the `Contents/Home`
+/// of macOS bundles). `None` for the entries that would end up outside of the runtime.
+fn runtime_entry_path(name: &Path) -> Option<PathBuf> {
+    let mut components = name.components();
+    components.next()?;
+    let rest = components.as_path();
+    let rest = rest.strip_prefix("Contents/Home").unwrap_or(rest);
+    if rest.as_os_str().is_empty() || rest.components().any(|x| !matches!(x, Component::Normal(_))) {
+        return None;
+    }
+    Some(rest.to_path_buf())
+}
+
+fn runtime_files(runtime_manifest: &Value, java: &String) -> Result<Vec<DownloadTask>, AppError> {
+    let runtime_dir = get_java_dir().join(java);
 
     let files_map = runtime_manifest
         .get("files")
         .and_then(|f| f.as_object())
         .ok_or_else(|| AppError::ManifestParseFailed("Missing 'files' object in Java runtime manifest".to_string()))?;
 
+    let mut tasks = Vec::new();
     for (k, v) in files_map {
         let file_type = v.get("type").and_then(|t| t.as_str()).unwrap_or("");
 
@@ -85,20 +336,19 @@ pub async fn download_java(
                 .and_then(|u| u.as_str())
                 .ok_or_else(|| AppError::ManifestParseFailed(format!("Missing URL for file {}", k)))?;
 
-            let file_url = mirror.parse_url(&file_url_str.to_string());
             let size = download_raw.get("size").and_then(|s| s.as_u64()).unwrap_or(0);
             let sha1 = download_raw.get("sha1").and_then(|s| s.as_str()).unwrap_or("");
 
-            create_dir_all(&runtime_dir).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
-
-            info!("Downloading {} ({} bytes)", file_url, size);
-            download_file_if_not_exists(&runtime_dir.join(k), file_url.to_string(), sha1, size).await?;
-        } else {
-            create_dir_all(runtime_dir.join(k))
-                .map_err(|e| AppError::DirCreateFailed(format!("Failed to create directory {}: {}", k, e)))?;
+            tasks.push(DownloadTask::new(file_url_str.to_string(), runtime_dir.join(k), sha1, size));
         }
     }
+    Ok(tasks)
+}
 
+/// Marks the runtime as installed by writing its `release` file, which is what `get_java` reads the version from.
+pub fn write_java_release(java: &String, version: &String) -> Result<(), AppError> {
+    let runtime_dir = get_java_dir().join(java);
+    create_dir_all(&runtime_dir).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
     let release_file_path = runtime_dir.join("release");
     if !release_file_path.exists() {
         fs::write(
@@ -0,0 +1,177 @@
+use crate::models::config::DownloadSettings;
+use crate::models::downloader::VersionLoader;
+use crate::models::error::{AppError, Void};
+use crate::models::liteloader::{LiteLoaderBuild, LiteLoaderLibrary, LiteLoaderManifest};
+use crate::models::versions::MinecraftVersion;
+use crate::services::directory_manager::{get_version_directory, get_version_manifest};
+use crate::services::game_downloader::download_from_manifest;
+use crate::services::http_client::HttpClient;
+use crate::services::metadata_cache::get_cached_json;
+use crate::services::utils::MAVEN_CENTRAL;
+use crate::services::version_manager::load_version_manifest;
+use crate::GLOBAL_CACHE;
+use serde_json::{json, Value};
+use std::fs;
+use std::fs::create_dir_all;
+
+const LITELOADER_VERSIONS_URL: &str = "https://dl.liteloader.com/versions/versions.json";
+const LAUNCHWRAPPER_MAIN_CLASS: &str = "net.minecraft.launchwrapper.Launch";
+const MOJANG_LIBRARIES: &str = "https://libraries.minecraft.net/";
+
+async fn load_liteloader_manifest(
+    client: &HttpClient,
+    settings: &DownloadSettings,
+) -> Result<LiteLoaderManifest, AppError> {
+    let mut global_cache = GLOBAL_CACHE.lock().await;
+    if global_cache.liteloader.is_none() {
+        let manifest: LiteLoaderManifest = get_cached_json(client, LITELOADER_VERSIONS_URL, settings).await?;
+        global_cache.liteloader = Some(manifest);
+    }
+    global_cache
+        .liteloader
+        .clone()
+        .ok_or_else(|| AppError::Internal("The LiteLoader manifest wasn't cached".to_string()))
+}
+
+/// Lists the LiteLoader builds of the given Minecraft version as installed ids (e.g. `1.10.2-LiteLoader1.10.2`),
+/// releases before snapshots.
+pub async fn get_available_liteloader_versions(
+    client: &HttpClient,
+    version_id: &String,
+    settings: &DownloadSettings,
+) -> Result<Vec<String>, AppError> {
+    let manifest = load_liteloader_manifest(client, settings).await?;
+    let Some(entry) = manifest.versions.get(version_id) else {
+        return Ok(Vec::new());
+    };
+    let mut result = Vec::new();
+    for branch in [&entry.artefacts, &entry.snapshots].into_iter().flatten() {
+        let mut builds = branch
+            .builds
+            .iter()
+            .filter(|(key, _)| *key != "latest")
+            .map(|(_, build)| format!("{version_id}-LiteLoader{}", build.version))
+            .collect::<Vec<_>>();
+        builds.sort();
+        builds.reverse();
+        result.extend(builds);
+    }
+    Ok(result)
+}
+
+/// Splits an installed id back into its Minecraft version and LiteLoader build.
+fn parse_installed_id(id: &str) -> Result<(&str, &str), AppError> {
+    id.split_once("-LiteLoader")
+        .ok_or_else(|| AppError::ManifestParseFailed(format!("Invalid LiteLoader version {id}")))
+}
+
+/// Finds the build of the given LiteLoader version alongside the repository hosting it.
+async fn find_build(
+    client: &HttpClient,
+    version_loader: &VersionLoader,
+    settings: &DownloadSettings,
+) -> Result<(LiteLoaderBuild, String), AppError> {
+    let (mc_version, build_version) = parse_installed_id(&version_loader.id)?;
+    let manifest = load_liteloader_manifest(client, settings).await?;
+    let entry = manifest.versions.get(mc_version).ok_or(AppError::VersionNotFound)?;
+    for branch in [&entry.artefacts, &entry.snapshots].into_iter().flatten() {
+        let Some(build) = branch.builds.values().find(|x| x.version == build_version) else {
+            continue;
+        };
+        let repo = branch
+            .repo
+            .as_ref()
+            .or(entry.repo.as_ref())
+            .ok_or_else(|| AppError::ManifestParseFailed(format!("LiteLoader {build_version} has no repository")))?;
+        return Ok((build.clone(), repo.url.replace("http://", "https://")));
+    }
+    Err(AppError::VersionNotFound)
+}
+
+/// The version JSON LiteLoader's own installer would write: it inherits the vanilla version and launches it through
+/// LaunchWrapper with LiteLoader's tweaker appended to the vanilla `minecraftArguments`.
+pub async fn liteloader_version_json(
+    client: &HttpClient,
+    version_loader: &VersionLoader,
+    minecraft_arguments: &str,
+    settings: &DownloadSettings,
+) -> Result<Value, AppError> {
+    let (mc_version, _) = parse_installed_id(&version_loader.id)?;
+    let (build, repo_url) = find_build(client, version_loader, settings).await?;
+
+    let mut libraries = vec![json!({
+        "name": format!("com.mumfrey:liteloader:{}", build.version),
+        "url": repo_url,
+    })];
+    libraries.extend(build.libraries.iter().map(|x| json!({ "name": x.name, "url": library_url(x) })));
+
+    let mut arguments = format!("--tweakClass {}", build.tweak_class);
+    if !minecraft_arguments.is_empty() {
+        arguments = format!("{minecraft_arguments} {arguments}");
+    }
+    Ok(json!({
+        "id": version_loader.get_installed_id(),
+        "inheritsFrom": mc_version,
+        "jar": mc_version,
+        "type": "release",
+        "time": build.timestamp.clone().unwrap_or_default(),
+        "releaseTime": build.timestamp.unwrap_or_default(),
+        "mainClass": LAUNCHWRAPPER_MAIN_CLASS,
+        "minecraftArguments": arguments,
+        "libraries": libraries,
+    }))
+}
+
+/// LiteLoader's own libraries leave the repository out, LaunchWrapper is on Mojang's and ASM on Maven Central.
+fn library_url(library: &LiteLoaderLibrary) -> String {
+    match &library.url {
+        Some(url) => url.replace("http://", "https://"),
+        None if library.name.starts_with("org.ow2.asm:") => MAVEN_CENTRAL.to_string(),
+        None => MOJANG_LIBRARIES.to_string(),
+    }
+}
+
+/// Writes the version JSON of the given LiteLoader build, after fetching the vanilla one it inherits.
+pub async fn install_liteloader(
+    client: &HttpClient,
+    version_loader: &VersionLoader,
+    settings: &DownloadSettings,
+) -> Void {
+    let (mc_version, _) = parse_installed_id(&version_loader.id)?;
+    let manifest = load_version_manifest(client, settings).await?;
+    download_from_manifest(client, &mc_version.to_string(), &manifest, &settings.mirrors).await?;
+
+    let vanilla = MinecraftVersion::from_id(mc_version.to_string()).load_json();
+    let minecraft_arguments = vanilla
+        .get("minecraftArguments")
+        .and_then(|x| x.as_str())
+        .ok_or_else(|| AppError::LaunchArgsNotFound(format!("LiteLoader can't be installed on {mc_version}")))?;
+    let version_json = liteloader_version_json(client, version_loader, minecraft_arguments, settings).await?;
+
+    let version_id = version_loader.get_installed_id();
+    create_dir_all(get_version_directory(&version_id)).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    let content = serde_json::to_string(&version_json).map_err(|e| AppError::JsonParseFailed(e.to_string()))?;
+    fs::write(get_version_manifest(&version_id), content)
+        .map_err(|e| AppError::FileWriteFailed(format!("Failed to write {version_id}.json: {e}")))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn installed_ids_split_into_minecraft_and_build() {
+        assert_eq!(parse_installed_id("1.12.2-LiteLoader1.12.2").unwrap(), ("1.12.2", "1.12.2"));
+        assert_eq!(
+            parse_installed_id("1.12.2-LiteLoader1.12.2-SNAPSHOT").unwrap(),
+            ("1.12.2", "1.12.2-SNAPSHOT")
+        );
+    }
+
+    #[test]
+    fn ids_without_liteloader_are_rejected() {
+        assert!(parse_installed_id("1.12.2").is_err());
+        assert!(parse_installed_id("1.12.2-forge-14.23.5.2860").is_err());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::models::config::DownloadSettings;
+use crate::models::error::{AppError, Void};
+use crate::models::metadata::CachedMetadata;
+use crate::models::mirror::Mirror;
+use crate::services::directory_manager::get_metadata_cache_directory;
+use crate::services::http_client::{check_status, classify_error, HttpClient};
+use crate::services::mirror_manager::{candidate_urls, should_fail_over};
+use log::info;
+use reqwest::header::{HeaderName, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Response, StatusCode};
+use serde::de::DeserializeOwned;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Fetches `url` as text through the metadata cache, see `get_cached`.
+pub async fn get_cached_text(client: &HttpClient, url: &str, settings: &DownloadSettings) -> Result<String, AppError> {
+    get_cached(client, url, settings, |body| Ok(body.to_string())).await
+}
+
+/// Fetches and parses `url` through the metadata cache, see `get_cached`.
+pub async fn get_cached_json<T: DeserializeOwned>(
+    client: &HttpClient,
+    url: &str,
+    settings: &DownloadSettings,
+) -> Result<T, AppError> {
+    get_cached(client, url, settings, |body| {
+        serde_json::from_str(body)
+            .map_err(|e| AppError::JsonParseFailed(format!("Failed to parse the response of {url}: {e}")))
+    })
+    .await
+}
+
+/// Serves a cached response that's younger than the configured TTL as is. older ones get revalidated through the
+/// mirrors with a conditional request, and when none of them can be reached the cached response is served no
+/// matter its age so the launcher keeps working offline.
+///
+/// `parse` is applied to every response before it's cached, a mirror serving something unparsable gets skipped.
+async fn get_cached<T>(
+    client: &HttpClient,
+    url: &str,
+    settings: &DownloadSettings,
+    parse: impl Fn(&str) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let mut cached = load_entry(url);
+    if let Some(entry) = &cached {
+        let fresh = now().saturating_sub(entry.fetched_at) < settings.metadata_cache_ttl;
+        match parse(&entry.body) {
+            Ok(value) if fresh => return Ok(value),
+            Ok(_) => {}
+            Err(e) => {
+                // Revalidating it would only get the same broken body confirmed, it's fetched from scratch instead.
+                info!("Cached copy of {url} is broken, fetching it again: {e}");
+                cached = None;
+            }
+        }
+    }
+
+    match fetch_entry(client, url, cached.as_ref(), &settings.mirrors, &parse).await {
+        Ok((entry, value)) => {
+            save_entry(&entry)?;
+            Ok(value)
+        }
+        Err(e) if should_fail_over(&e) => {
+            let Some(entry) = cached else {
+                return Err(e);
+            };
+            info!("Couldn't revalidate {url}, serving the copy cached at {}: {e}", entry.fetched_at);
+            parse(&entry.body)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// The cached response of `url` regardless of its age, if there is one.
+pub fn get_cached_entry(url: &str) -> Option<CachedMetadata> {
+    load_entry(url)
+}
+
+/// Forgets every cached response, the next request of each fetches it from scratch.
+pub fn clear_metadata_cache() -> Void {
+    let directory = get_metadata_cache_directory();
+    if directory.exists() {
+        fs::remove_dir_all(&directory).map_err(|e| AppError::FileDeleteFailed(e.to_string()))?;
+    }
+    fs::create_dir_all(&directory).map_err(|e| AppError::DirCreateFailed(e.to_string()))
+}
+
+async fn fetch_entry<T>(
+    client: &HttpClient,
+    url: &str,
+    cached: Option<&CachedMetadata>,
+    mirrors: &[Mirror],
+    parse: &impl Fn(&str) -> Result<T, AppError>,
+) -> Result<(CachedMetadata, T), AppError> {
+    let mut last_error = None;
+    for (source, candidate) in candidate_urls(url, mirrors) {
+        let validated = cached.filter(|x| x.source == candidate);
+        let result = fetch_candidate(client, url, &candidate, validated)
+            .await
+            .and_then(|entry| parse(&entry.body).map(|value| (entry, value)));
+        match result {
+            Ok(result) => {
+                info!("{url} was served by {source}");
+                return Ok(result);
+            }
+            Err(e) if should_fail_over(&e) => {
+                info!("{source} failed to serve {candidate}: {e}");
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or(AppError::DownloadFailed))
+}
+
+async fn fetch_candidate(
+    client: &HttpClient,
+    url: &str,
+    candidate: &str,
+    cached: Option<&CachedMetadata>,
+) -> Result<CachedMetadata, AppError> {
+    let mut request = client.get(candidate);
+    if let Some(entry) = cached {
+        if let Some(etag) = &entry.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+    let resp = client.send(candidate, request).await?;
+    if let Some(entry) = cached.filter(|_| resp.status() == StatusCode::NOT_MODIFIED) {
+        return Ok(CachedMetadata {
+            fetched_at: now(),
+            ..entry.clone()
+        });
+    }
+
+    let resp = check_status(candidate, resp)?;
+    let etag = header(&resp, ETAG);
+    let last_modified = header(&resp, LAST_MODIFIED);
+    let body = resp.text().await.map_err(|e| classify_error(candidate, &e))?;
+    Ok(CachedMetadata {
+        url: url.to_string(),
+        source: candidate.to_string(),
+        etag,
+        last_modified,
+        fetched_at: now(),
+        body,
+    })
+}
+
+fn header(resp: &Response, name: HeaderName) -> Option<String> {
+    resp.headers()
+        .get(name)
+        .and_then(|x| x.to_str().ok())
+        .map(|x| x.to_string())
+}
+
+/// Responses are stored under the SHA1 of their origin url, so every mirror shares the same entry. only the mirror
+/// that served it gets asked to revalidate it though.
+fn entry_file(url: &str) -> PathBuf {
+    let hash = Sha1::digest(url.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    get_metadata_cache_directory().join(format!("{hash}.json"))
+}
+
+fn load_entry(url: &str) -> Option<CachedMetadata> {
+    let content = fs::read_to_string(entry_file(url)).ok()?;
+    serde_json::from_str::<CachedMetadata>(&content)
+        .ok()
+        .filter(|x| x.url == url)
+}
+
+fn save_entry(entry: &CachedMetadata) -> Void {
+    fs::create_dir_all(get_metadata_cache_directory()).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    let content = serde_json::to_string(entry).map_err(|x| AppError::JsonParseFailed(x.to_string()))?;
+    fs::write(entry_file(&entry.url), content).map_err(|x| AppError::FileWriteFailed(x.to_string()))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default()
+}
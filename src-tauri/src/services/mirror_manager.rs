@@ -0,0 +1,327 @@
+use crate::models::config::DownloadSettings;
+use crate::models::error::{AppError, Void};
+use crate::models::mirror::{list_mirrors, Mirror, MirrorBenchmark};
+use crate::services::directory_manager::get_mirror_benchmarks_file;
+use crate::services::http_client::{check_status, classify_error, HttpClient};
+use log::info;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinSet;
+
+pub const ORIGIN: &str = "Origin";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Benchmarks older than this get retaken before the auto mode relies on them.
+const BENCHMARK_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Every url worth trying for `url` alongside the name of whoever serves it, following the order of the given
+/// mirrors and ending with the origin itself. mirrors that don't rewrite the url (other than upgrading it to https)
+/// are folded into the origin.
+pub fn candidate_urls(url: &str, mirrors: &[Mirror]) -> Vec<(String, String)> {
+    let origin = url.to_string();
+    let mut candidates: Vec<(String, String)> = Vec::new();
+    for mirror in mirrors {
+        let candidate = mirror.parse_url(&origin);
+        if same_location(&candidate, &origin) || candidates.iter().any(|(_, x)| same_location(x, &candidate)) {
+            continue;
+        }
+        candidates.push((mirror.name.clone(), candidate));
+    }
+    candidates.push((ORIGIN.to_string(), origin));
+    candidates
+}
+
+/// Whether two urls point at the same place, regardless of them being fetched over http or https.
+fn same_location(a: &str, b: &str) -> bool {
+    fn strip_scheme(url: &str) -> &str {
+        url.split_once("://").map_or(url, |(_, rest)| rest)
+    }
+    strip_scheme(a).eq_ignore_ascii_case(strip_scheme(b))
+}
+
+/// Whether the error is the mirror's fault, meaning the next mirror may still be able to serve the file.
+pub fn should_fail_over(e: &AppError) -> bool {
+    matches!(
+        e,
+        AppError::HttpStatus(..)
+            | AppError::RequestTimedOut(_)
+            | AppError::ConnectionFailed(_)
+            | AppError::NetworkRequestFailed(_)
+            | AppError::FileVerificationFailed(_)
+            | AppError::JsonParseFailed(_)
+    )
+}
+
+/// Fetches `url` as text from the first mirror that manages to serve it.
+pub async fn get_text_from_mirrors(client: &HttpClient, url: &str, mirrors: &[Mirror]) -> Result<String, AppError> {
+    let mut last_error = None;
+    for (source, candidate) in candidate_urls(url, mirrors) {
+        match client.get_text(&candidate).await {
+            Ok(text) => {
+                info!("{url} was served by {source}");
+                return Ok(text);
+            }
+            Err(e) if should_fail_over(&e) => {
+                info!("{source} failed to serve {candidate}: {e}");
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or(AppError::DownloadFailed))
+}
+
+/// Fetches and parses `url` from the first mirror that manages to serve valid JSON.
+pub async fn get_json_from_mirrors<T: DeserializeOwned>(
+    client: &HttpClient,
+    url: &str,
+    mirrors: &[Mirror],
+) -> Result<T, AppError> {
+    let mut last_error = None;
+    for (source, candidate) in candidate_urls(url, mirrors) {
+        match client.get_json(&candidate).await {
+            Ok(value) => {
+                info!("{url} was served by {source}");
+                return Ok(value);
+            }
+            Err(e) if should_fail_over(&e) => {
+                info!("{source} failed to serve {candidate}: {e}");
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(last_error.unwrap_or(AppError::DownloadFailed))
+}
+
+/// A small file each known origin host serves, relative to the host. hosts without one are only measured for latency.
+fn probe_path(origin: &str) -> Option<&'static str> {
+    match origin {
+        "https://launchermeta.mojang.com/" => Some("mc/game/version_manifest.json"),
+        "https://piston-meta.mojang.com/" => Some("mc/game/version_manifest_v2.json"),
+        "https://piston-data.mojang.com/" => {
+            Some("v1/objects/bd65e7d2e3c237be76cfbef4c2405033d7f91521/client-1.12.xml")
+        }
+        "https://libraries.minecraft.net/" => Some("com/mojang/patchy/1.1/patchy-1.1.jar"),
+        "https://meta.fabricmc.net/" => Some("v2/versions/installer"),
+        "https://meta.quiltmc.org/" => Some("v3/versions/installer"),
+        "https://api.modrinth.com/" => Some("v2/tag/loader"),
+        _ => None,
+    }
+}
+
+struct ProbeResult {
+    latency: Duration,
+    bytes: u64,
+    elapsed: Duration,
+}
+
+async fn probe(client: &HttpClient, base: &str, path: Option<&str>) -> Result<ProbeResult, AppError> {
+    let url = format!("{base}{}", path.unwrap_or(""));
+    let start = Instant::now();
+    let resp = client
+        .without_retries()
+        .get(&url)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| classify_error(&url, &e))?;
+    let latency = start.elapsed();
+    if path.is_none() {
+        // Only the host itself is probed, any answer that isn't a server error means it's up.
+        if resp.status().is_server_error() {
+            return Err(AppError::HttpStatus(resp.status().as_u16(), url));
+        }
+        return Ok(ProbeResult {
+            latency,
+            bytes: 0,
+            elapsed: latency,
+        });
+    }
+    let mut resp = check_status(&url, resp)?;
+    let mut bytes = 0;
+    while let Some(chunk) = resp.chunk().await.map_err(|e| classify_error(&url, &e))? {
+        bytes += chunk.len() as u64;
+    }
+    Ok(ProbeResult {
+        latency,
+        bytes,
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Measures the latency and throughput of every host of the mirror.
+pub async fn benchmark_mirror(client: &HttpClient, mirror: &Mirror) -> MirrorBenchmark {
+    let mut latencies = Vec::new();
+    let mut bytes = 0;
+    let mut elapsed = Duration::ZERO;
+    let mut error = None;
+    for (origin, base) in mirror.all_hosts() {
+        match probe(client, &base, probe_path(&origin)).await {
+            Ok(result) => {
+                latencies.push(result.latency);
+                if result.bytes > 0 {
+                    bytes += result.bytes;
+                    elapsed += result.elapsed;
+                }
+            }
+            Err(e) => {
+                info!("Benchmarking {base} of {} failed: {e}", mirror.name);
+                error = Some(e.to_string());
+            }
+        }
+    }
+    let latency_ms = if latencies.is_empty() {
+        None
+    } else {
+        Some((latencies.iter().sum::<Duration>() / latencies.len() as u32).as_millis() as u64)
+    };
+    let throughput = if elapsed.is_zero() {
+        None
+    } else {
+        Some((bytes as f64 / elapsed.as_secs_f64()) as u64)
+    };
+    MirrorBenchmark {
+        name: mirror.name.clone(),
+        latency_ms,
+        throughput,
+        healthy: error.is_none() && latency_ms.is_some(),
+        error,
+        tested_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.as_secs())
+            .unwrap_or_default(),
+    }
+}
+
+/// Benchmarks every available mirror at the same time and stores the results.
+pub async fn benchmark_mirrors(client: &HttpClient) -> Result<Vec<MirrorBenchmark>, AppError> {
+    let mut workers = JoinSet::new();
+    for mirror in list_mirrors()? {
+        let client = client.clone();
+        workers.spawn(async move { benchmark_mirror(&client, &mirror).await });
+    }
+    let mut results = Vec::new();
+    while let Some(result) = workers.join_next().await {
+        results.push(result.map_err(|e| AppError::Internal(format!("Benchmark worker failed: {e}")))?);
+    }
+    results.sort_by(compare_benchmarks);
+    save_benchmarks(&results)?;
+    Ok(results)
+}
+
+/// Healthy mirrors first, then the ones with the highest throughput and finally the lowest latency.
+fn compare_benchmarks(a: &MirrorBenchmark, b: &MirrorBenchmark) -> std::cmp::Ordering {
+    b.healthy
+        .cmp(&a.healthy)
+        .then(b.throughput.unwrap_or(0).cmp(&a.throughput.unwrap_or(0)))
+        .then(a.latency_ms.unwrap_or(u64::MAX).cmp(&b.latency_ms.unwrap_or(u64::MAX)))
+}
+
+/// The results of the latest benchmark, empty if there was none.
+pub fn load_benchmarks() -> Vec<MirrorBenchmark> {
+    fs::read_to_string(get_mirror_benchmarks_file())
+        .ok()
+        .and_then(|x| serde_json::from_str(&x).ok())
+        .unwrap_or_default()
+}
+
+fn save_benchmarks(results: &[MirrorBenchmark]) -> Void {
+    let content = serde_json::to_string(results).map_err(|x| AppError::JsonParseFailed(x.to_string()))?;
+    fs::write(get_mirror_benchmarks_file(), content).map_err(|x| AppError::FileWriteFailed(x.to_string()))
+}
+
+/// When the auto mirror mode is on, moves the fastest healthy mirror of the settings' mirror list to its front.
+/// the benchmark gets retaken first if it's missing or outdated, the rest of the list stays as its fallbacks.
+/// failing to benchmark the mirrors leaves the configured order as it is rather than failing the install.
+pub async fn apply_auto_mirror(client: &HttpClient, settings: &mut DownloadSettings) -> Void {
+    if !settings.auto_mirror.boolean() {
+        return Ok(());
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default();
+    let mut benchmarks = load_benchmarks();
+    let outdated = benchmarks
+        .iter()
+        .map(|x| x.tested_at)
+        .min()
+        .map(|x| now.saturating_sub(x) > BENCHMARK_TTL.as_secs())
+        .unwrap_or(true);
+    if outdated {
+        benchmarks = match benchmark_mirrors(client).await {
+            Ok(benchmarks) => benchmarks,
+            Err(e) => {
+                info!("Failed to benchmark the mirrors, keeping the configured order: {e}");
+                return Ok(());
+            }
+        };
+    }
+    benchmarks.sort_by(compare_benchmarks);
+
+    let Some(fastest) = benchmarks
+        .iter()
+        .filter(|x| x.healthy)
+        .find_map(|x| settings.mirrors.iter().position(|m| m.name == x.name))
+    else {
+        info!("None of the configured mirrors is healthy, keeping the configured order.");
+        return Ok(());
+    };
+    let fastest = settings.mirrors.remove(fastest);
+    info!("Auto mirror mode picked {}", fastest.name);
+    settings.mirrors.insert(0, fastest);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::mirror::{mojang_mirror, ninecraft_mirror};
+
+    const CLIENT_JAR: &str = "https://piston-data.mojang.com/v1/objects/abc/client.jar";
+
+    #[test]
+    fn candidate_urls_end_with_the_origin() {
+        let candidates = candidate_urls(CLIENT_JAR, &[ninecraft_mirror()]);
+        assert_eq!(
+            candidates,
+            vec![
+                ("9Craft".to_string(), "https://piston-data.9craft.ir/v1/objects/abc/client.jar".to_string()),
+                (ORIGIN.to_string(), CLIENT_JAR.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn candidate_urls_fold_mirrors_that_change_nothing() {
+        let candidates = candidate_urls(CLIENT_JAR, &[mojang_mirror(), ninecraft_mirror(), ninecraft_mirror()]);
+        let sources = candidates.iter().map(|(source, _)| source.as_str()).collect::<Vec<_>>();
+        assert_eq!(sources, vec!["9Craft", ORIGIN]);
+    }
+
+    #[test]
+    fn candidate_urls_fold_mirrors_that_only_upgrade_to_https() {
+        let url = "http://resources.download.minecraft.net/ab/abcdef";
+        assert_eq!(
+            candidate_urls(url, &[mojang_mirror()]),
+            vec![(ORIGIN.to_string(), url.to_string())]
+        );
+    }
+
+    #[test]
+    fn candidate_urls_without_mirrors_is_the_origin() {
+        assert_eq!(candidate_urls(CLIENT_JAR, &[]), vec![(ORIGIN.to_string(), CLIENT_JAR.to_string())]);
+    }
+
+    #[test]
+    fn only_mirror_faults_fail_over() {
+        assert!(should_fail_over(&AppError::HttpStatus(404, CLIENT_JAR.to_string())));
+        assert!(should_fail_over(&AppError::RequestTimedOut(CLIENT_JAR.to_string())));
+        assert!(should_fail_over(&AppError::ConnectionFailed(CLIENT_JAR.to_string())));
+        assert!(should_fail_over(&AppError::FileVerificationFailed(CLIENT_JAR.to_string())));
+        assert!(should_fail_over(&AppError::JsonParseFailed(String::new())));
+        assert!(!should_fail_over(&AppError::FileWriteFailed(String::new())));
+        assert!(!should_fail_over(&AppError::DownloadCancelled(String::new())));
+    }
+}
@@ -6,3 +6,18 @@ pub mod version_manager;
 pub(crate) mod jdk_manager;
 pub mod utils;
 pub mod mod_manager;
+pub mod download_pool;
+pub mod http_client;
+pub mod download_manager;
+pub mod install_planner;
+pub mod mirror_manager;
+pub mod metadata_cache;
+pub mod bandwidth_limiter;
+pub mod object_store;
+pub mod garbage_collector;
+pub mod verifier;
+pub mod asset_layout;
+pub mod rule_engine;
+pub mod forge_installer;
+pub mod liteloader;
+pub mod optifine_installer;
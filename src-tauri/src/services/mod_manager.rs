@@ -1,6 +1,6 @@
 use crate::services::directory_manager::get_mods_directory;
 use crate::models::error::AppError;
-use crate::models::mods::{FabricModInfo, McModInfo};
+use crate::models::mods::{FabricModInfo, McModInfo, QuiltModJson};
 use crate::models::mods::ModInfo;
 use std::fs;
 use std::fs::File;
@@ -84,6 +84,26 @@ pub fn load_mod(zip: Mutex<ZipArchive<File>>, path: String) -> Result<ModInfo, A
         return load_from_toml(&toml, path);
     }
 
+    // Quilt, checked before Fabric since Quilt mods may ship a fabric.mod.json too
+    if let Ok(mut file) = zip_guard.by_name("quilt.mod.json") {
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .map_err(|e| AppError::FileReadFailed(format!("Failed to read quilt.mod.json: {}", e)))?;
+
+        let info: QuiltModJson = serde_json::from_str(content.as_str())
+            .map_err(|e| AppError::JsonParseFailed(format!("Failed to parse quilt.mod.json: {}", e)))?;
+        let info = info.quilt_loader;
+
+        return Ok(ModInfo {
+            path,
+            name: info.metadata.name.unwrap_or_else(|| info.mod_id.clone()),
+            mod_id: info.mod_id,
+            version: info.version,
+            description: info.metadata.description.unwrap_or_default(),
+            enabled,
+        });
+    }
+
     // Fabric
     if let Ok(mut file) = zip_guard.by_name("fabric.mod.json") {
         let mut content = String::new();
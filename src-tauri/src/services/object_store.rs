@@ -0,0 +1,146 @@
+use crate::models::error::{AppError, Void};
+use crate::services::directory_manager::get_object_store_directory;
+use log::info;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::fs::{create_dir_all, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// Where the object with the given SHA1 lives in the store, e.g. `store/ab/ab12...`.
+pub fn object_path(sha1: &str) -> PathBuf {
+    let sha1 = sha1.to_lowercase();
+    let prefix = sha1.get(0..2).unwrap_or("00").to_string();
+    get_object_store_directory().join(prefix).join(sha1)
+}
+
+/// Puts the stored object with the given SHA1 at `dest`, returns false when the store doesn't have it.
+pub fn link_from_store(sha1: &str, dest: &Path) -> Result<bool, AppError> {
+    let object = object_path(sha1);
+    if sha1.is_empty() || !object.is_file() {
+        return Ok(false);
+    }
+    place(&object, dest)?;
+    Ok(true)
+}
+
+/// Adds a downloaded and verified file to the store, every later copy of it gets linked from there.
+pub fn add_to_store(path: &Path, sha1: &str) -> Void {
+    let object = object_path(sha1);
+    if sha1.is_empty() || object.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = object.parent() {
+        create_dir_all(parent).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    }
+    if fs::hard_link(path, &object).is_err() {
+        // Most likely the store is on another drive, a copy still spares the next download.
+        fs::copy(path, &object).map_err(|e| AppError::FileCopyFailed(format!("{}: {e}", object.display())))?;
+    }
+    Ok(())
+}
+
+/// Extracts the files of the natives jar into `dest` through the store, so a native shared by several versions
+/// sits on the disk only once. the paths starting with any of `exclude` stay in the jar.
+pub fn extract_into_store(archive: &Path, dest: &Path, exclude: &[String]) -> Void {
+    let file = File::open(archive).map_err(|e| AppError::FileReadFailed(format!("Failed to open classifier: {}", e)))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+    create_dir_all(dest).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+        if entry.is_dir() {
+            continue;
+        }
+        if is_excluded(entry.name(), exclude) {
+            continue;
+        }
+        let Some(name) = entry.enclosed_name() else {
+            info!("Skipping {} of {}, it points outside of the natives directory", entry.name(), archive.display());
+            continue;
+        };
+        let mut content = Vec::new();
+        entry
+            .read_to_end(&mut content)
+            .map_err(|e| AppError::ZipExtractionFailed(format!("{}: {e}", entry.name())))?;
+        let sha1 = store_bytes(&content)?;
+        place(&object_path(&sha1), &dest.join(name))?;
+    }
+    Ok(())
+}
+
+/// The paths of the files `extract_into_store` puts in the natives directory from this archive, relative to it.
+pub fn archive_entries(archive: &Path, exclude: &[String]) -> Result<Vec<PathBuf>, AppError> {
+    let file = File::open(archive).map_err(|e| AppError::FileReadFailed(format!("Failed to open classifier: {}", e)))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+    let mut entries = Vec::new();
+    for i in 0..zip.len() {
+        let entry = zip.by_index(i).map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+        if entry.is_dir() || is_excluded(entry.name(), exclude) {
+            continue;
+        }
+        if let Some(name) = entry.enclosed_name() {
+            entries.push(name);
+        }
+    }
+    Ok(entries)
+}
+
+/// The SHA1s of the files `extract_into_store` takes from this archive, i.e. the stored objects it links to.
+pub fn archive_hashes(archive: &Path, exclude: &[String]) -> Result<Vec<String>, AppError> {
+    let file = File::open(archive).map_err(|e| AppError::FileReadFailed(format!("Failed to open classifier: {}", e)))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+    let mut hashes = Vec::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+        if entry.is_dir() || is_excluded(entry.name(), exclude) || entry.enclosed_name().is_none() {
+            continue;
+        }
+        let mut content = Vec::new();
+        entry
+            .read_to_end(&mut content)
+            .map_err(|e| AppError::ZipExtractionFailed(format!("{}: {e}", entry.name())))?;
+        hashes.push(sha1_hex(&content));
+    }
+    Ok(hashes)
+}
+
+fn is_excluded(name: &str, exclude: &[String]) -> bool {
+    exclude.iter().any(|x| name.starts_with(x.as_str()))
+}
+
+/// Writes the content into the store unless it's already there, returns its SHA1.
+fn store_bytes(content: &[u8]) -> Result<String, AppError> {
+    let sha1 = sha1_hex(content);
+    let object = object_path(&sha1);
+    if !object.exists() {
+        if let Some(parent) = object.parent() {
+            create_dir_all(parent).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+        }
+        // Written under a temporary name first so a half written object never ends up in the store.
+        let part = object.with_extension("part");
+        fs::write(&part, content).map_err(|e| AppError::FileWriteFailed(e.to_string()))?;
+        fs::rename(&part, &object).map_err(|e| AppError::FileRenameFailed(e.to_string()))?;
+    }
+    Ok(sha1)
+}
+
+fn sha1_hex(content: &[u8]) -> String {
+    Sha1::digest(content).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hard links the object to `dest`, replacing whatever is there, and falls back to a copy when linking isn't
+/// possible (e.g. across drives).
+pub fn place(object: &Path, dest: &Path) -> Void {
+    if let Some(parent) = dest.parent() {
+        create_dir_all(parent).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    }
+    if dest.exists() {
+        fs::remove_file(dest).map_err(|e| AppError::FileDeleteFailed(format!("{}: {e}", dest.display())))?;
+    }
+    if fs::hard_link(object, dest).is_err() {
+        fs::copy(object, dest).map_err(|e| AppError::FileCopyFailed(format!("{}: {e}", dest.display())))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use crate::models::error::{AppError, Void};
+use crate::models::versions::MinecraftVersion;
+use crate::services::directory_manager::{
+    get_libraries_directory, get_mods_directory, get_version_directory, get_version_manifest,
+};
+use crate::services::jdk_manager::get_java;
+use crate::services::utils::patch_java_permission_linux;
+use log::info;
+use regex::bytes::Regex;
+use serde_json::{json, Value};
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::fs::{create_dir_all, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use zip::ZipArchive;
+
+const TWEAK_CLASS: &str = "optifine.OptiFineTweaker";
+const LAUNCHWRAPPER_MAIN_CLASS: &str = "net.minecraft.launchwrapper.Launch";
+const CONFIG_CLASSES: [&str; 2] = ["net/optifine/Config.class", "Config.class"];
+
+/// The Minecraft version and edition (e.g. `1.12.2` and `HD_U_G5`) an OptiFine installer was built for.
+struct OptiFineBuild {
+    minecraft: String,
+    edition: String,
+}
+
+impl OptiFineBuild {
+    fn library_name(&self) -> String {
+        format!("optifine:OptiFine:{}_{}", self.minecraft, self.edition)
+    }
+}
+
+/// Applies an OptiFine installer jar onto the given version. Forge versions get it as a mod, vanilla ones a version
+/// of their own inheriting them, launched through LaunchWrapper with OptiFine's tweaker. returns the version to
+/// launch. `temp_dir` is where the patcher gets to run, it's left for the caller to remove.
+pub async fn install_optifine(installer: &Path, target: &MinecraftVersion, temp_dir: &Path) -> Result<String, AppError> {
+    let file = File::open(installer).map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| AppError::ZipParseFailed(e.to_string()))?;
+    let build = read_build(&mut zip, installer)?;
+
+    let vanilla = target.get_inherited();
+    // Other loaders have a launch of their own that the LaunchWrapper version would replace (NeoForge ids contain
+    // "forge" too, but it has no OptiFine support either).
+    let is_forge = target.is_forge() && !target.id.to_lowercase().contains("neoforge");
+    if vanilla != *target && !is_forge {
+        return Err(AppError::OptiFineInstallFailed(format!(
+            "OptiFine can only be applied onto vanilla or Forge versions, not {}",
+            target.id
+        )));
+    }
+    if vanilla.id != build.minecraft {
+        return Err(AppError::OptiFineInstallFailed(format!(
+            "OptiFine {} is built for {}, not {}",
+            build.edition, build.minecraft, vanilla.id
+        )));
+    }
+    if !vanilla.is_installed() {
+        return Err(AppError::VersionNotFound);
+    }
+
+    if is_forge {
+        let dest = get_mods_directory().join(format!("OptiFine_{}_{}.jar", build.minecraft, build.edition));
+        info!("Placing OptiFine {} into {}", build.edition, dest.display());
+        create_dir_all(get_mods_directory()).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+        fs::copy(installer, &dest).map_err(|e| AppError::FileCopyFailed(e.to_string()))?;
+        return Ok(target.id.clone());
+    }
+
+    let vanilla_json = vanilla.load_json();
+    let library = get_libraries_directory().join(library_path(&build.library_name())?);
+    create_dir_all(library.parent().unwrap_or(Path::new("")))
+        .map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    if zip.by_name("optifine/Patcher.class").is_ok() {
+        run_patcher(installer, &vanilla, &vanilla_json, &library, temp_dir).await?;
+    } else {
+        // Older installers are the library themselves, OptiFine patches the classes while the game loads.
+        fs::copy(installer, &library).map_err(|e| AppError::FileCopyFailed(e.to_string()))?;
+    }
+
+    let mut libraries = vec![local_library(&build.library_name(), &library)?];
+    libraries.push(launchwrapper_library(&mut zip)?);
+
+    let id = format!("{}-OptiFine_{}", build.minecraft, build.edition);
+    let mut version_json = json!({
+        "id": id,
+        "inheritsFrom": vanilla.id,
+        "jar": vanilla.id,
+        "type": vanilla_json.get("type").cloned().unwrap_or(json!("release")),
+        "time": vanilla_json.get("time").cloned().unwrap_or_default(),
+        "releaseTime": vanilla_json.get("releaseTime").cloned().unwrap_or_default(),
+        "mainClass": LAUNCHWRAPPER_MAIN_CLASS,
+        "libraries": libraries,
+    });
+    // Legacy versions replace the whole argument line, newer ones get theirs merged with the inherited ones.
+    if let Some(arguments) = vanilla_json.get("minecraftArguments").and_then(|x| x.as_str()) {
+        version_json["minecraftArguments"] = json!(format!("{arguments} --tweakClass {TWEAK_CLASS}"));
+    } else {
+        version_json["arguments"] = json!({ "game": ["--tweakClass", TWEAK_CLASS] });
+    }
+
+    create_dir_all(get_version_directory(&id)).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    let content = serde_json::to_string(&version_json).map_err(|e| AppError::JsonParseFailed(e.to_string()))?;
+    fs::write(get_version_manifest(&id), content)
+        .map_err(|e| AppError::FileWriteFailed(format!("Failed to write {id}.json: {e}")))?;
+    info!("Installed OptiFine {} as {id}", build.edition);
+    Ok(id)
+}
+
+/// `Config.VERSION` is a compile time constant (e.g. `OptiFine_1.12.2_HD_U_G5`), so it's in the class file as is.
+/// installers whose config can't be found are recognized by their file name instead.
+fn read_build(zip: &mut ZipArchive<File>, installer: &Path) -> Result<OptiFineBuild, AppError> {
+    let pattern = Regex::new(r"OptiFine_([0-9][0-9.]*[0-9])_(HD_U_[A-Za-z0-9_]+)")
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let mut sources = Vec::new();
+    for name in CONFIG_CLASSES {
+        if let Ok(mut entry) = zip.by_name(name) {
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+            sources.push(bytes);
+        }
+    }
+    if let Some(file_name) = installer.file_name() {
+        sources.push(file_name.to_string_lossy().as_bytes().to_vec());
+    }
+    sources
+        .iter()
+        .find_map(|x| pattern.captures(x))
+        .map(|x| OptiFineBuild {
+            minecraft: String::from_utf8_lossy(&x[1]).into_owned(),
+            edition: String::from_utf8_lossy(&x[2]).into_owned(),
+        })
+        .ok_or_else(|| {
+            AppError::OptiFineInstallFailed(format!("{} isn't an OptiFine installer", installer.display()))
+        })
+}
+
+/// Installers that come with a patcher only ship the differences, applied onto the vanilla jar to get the library.
+async fn run_patcher(
+    installer: &Path,
+    vanilla: &MinecraftVersion,
+    vanilla_json: &Value,
+    library: &Path,
+    temp_dir: &Path,
+) -> Void {
+    let java_component = vanilla_json
+        .pointer("/javaVersion/component")
+        .and_then(|x| x.as_str())
+        .unwrap_or("jre-legacy")
+        .to_string();
+    let java = get_java(java_component)?;
+    patch_java_permission_linux(&java)?;
+    let vanilla_jar = get_version_directory(&vanilla.id).join(format!("{}.jar", vanilla.id));
+
+    info!("Patching {} with OptiFine", vanilla_jar.display());
+    create_dir_all(temp_dir).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    let mut command = Command::new(java.get_bin_file());
+    command
+        .arg("-cp")
+        .arg(installer)
+        .arg("optifine.Patcher")
+        .arg(&vanilla_jar)
+        .arg(installer)
+        .arg(library)
+        .current_dir(temp_dir);
+    let output = tauri::async_runtime::spawn_blocking(move || command.output())
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .map_err(|e| AppError::OptiFineInstallFailed(format!("Failed to start the patcher: {e}")))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        info!("[optifine_patcher][stdout] {line}");
+    }
+    for line in stderr.lines() {
+        info!("[optifine_patcher][stderr] {line}");
+    }
+    if !output.status.success() || !library.exists() {
+        let reason = stderr.lines().last().unwrap_or_default();
+        return Err(AppError::OptiFineInstallFailed(format!("The patcher exited with {}: {reason}", output.status)));
+    }
+    Ok(())
+}
+
+/// Recent installers bundle their own LaunchWrapper build, older ones rely on Mojang's.
+fn launchwrapper_library(zip: &mut ZipArchive<File>) -> Result<Value, AppError> {
+    let mut version = String::new();
+    if let Ok(mut entry) = zip.by_name("launchwrapper-of.txt") {
+        entry.read_to_string(&mut version).map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+    }
+    let version = version.trim();
+    if version.is_empty() {
+        return Ok(json!({ "name": "net.minecraft:launchwrapper:1.12" }));
+    }
+
+    let name = format!("optifine:launchwrapper-of:{version}");
+    let dest = get_libraries_directory().join(library_path(&name)?);
+    let mut entry = zip
+        .by_name(&format!("launchwrapper-of-{version}.jar"))
+        .map_err(|e| AppError::ZipParseFailed(format!("The installer has no launchwrapper-of-{version}.jar: {e}")))?;
+    create_dir_all(dest.parent().unwrap_or(Path::new(""))).map_err(|e| AppError::DirCreateFailed(e.to_string()))?;
+    let mut file = File::create(&dest).map_err(|e| AppError::FileCreateFailed(e.to_string()))?;
+    std::io::copy(&mut entry, &mut file).map_err(|e| AppError::ZipExtractionFailed(e.to_string()))?;
+    drop(file);
+    local_library(&name, &dest)
+}
+
+/// A library that only exists on the disk, described by its hash so it's verified rather than downloaded.
+fn local_library(name: &str, path: &PathBuf) -> Result<Value, AppError> {
+    let content = fs::read(path).map_err(|e| AppError::FileReadFailed(e.to_string()))?;
+    let sha1 = Sha1::digest(&content).iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    Ok(json!({
+        "name": name,
+        "downloads": {
+            "artifact": {
+                "path": library_path(name)?,
+                "url": "",
+                "sha1": sha1,
+                "size": content.len(),
+            }
+        }
+    }))
+}
+
+fn library_path(name: &str) -> Result<String, AppError> {
+    let args = name.split(':').collect::<Vec<&str>>();
+    if args.len() < 3 {
+        return Err(AppError::InvalidPath(format!("Invalid library name: {name}")));
+    }
+    let (group_id, artifact, version) = (args[0].replace('.', "/"), args[1], args[2]);
+    Ok(format!("{group_id}/{artifact}/{version}/{artifact}-{version}.jar"))
+}
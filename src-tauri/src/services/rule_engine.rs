@@ -0,0 +1,126 @@
+use crate::models::downloader::{Rule, RuleOS};
+use crate::models::launch_context::LaunchContext;
+use log::info;
+use regex::Regex;
+use serde_json::Value;
+
+/// Whether the rules let a library or argument through in the given context. without rules it always goes through,
+/// otherwise it's disallowed unless a rule allows it, and the last rule that applies has the final say.
+pub fn is_allowed(rules: Option<&[Rule]>, context: &LaunchContext) -> bool {
+    let Some(rules) = rules else {
+        return true;
+    };
+    rules
+        .iter()
+        .filter(|x| applies(x, context))
+        .last()
+        .map(|x| x.action == "allow")
+        .unwrap_or(false)
+}
+
+/// `is_allowed` for rules that are still raw JSON, rules that can't be parsed never allow anything.
+pub fn is_allowed_value(rules: Option<&Value>, context: &LaunchContext) -> bool {
+    let Some(rules) = rules.filter(|x| !x.is_null()) else {
+        return true;
+    };
+    match serde_json::from_value::<Vec<Rule>>(rules.clone()) {
+        Ok(rules) => is_allowed(Some(&rules), context),
+        Err(e) => {
+            info!("Ignoring what the malformed rules {rules} apply to: {e}");
+            false
+        }
+    }
+}
+
+fn applies(rule: &Rule, context: &LaunchContext) -> bool {
+    let os_matches = rule.os.as_ref().map(|x| os_applies(x, context)).unwrap_or(true);
+    let features_match = rule
+        .features
+        .as_ref()
+        .map(|x| x.iter().all(|(feature, enabled)| context.has_feature(feature) == *enabled))
+        .unwrap_or(true);
+    os_matches && features_match
+}
+
+fn os_applies(os: &RuleOS, context: &LaunchContext) -> bool {
+    if os.name.as_ref().is_some_and(|x| *x != context.os) {
+        return false;
+    }
+    if os.arch.as_ref().is_some_and(|x| *x != context.arch) {
+        return false;
+    }
+    match &os.version {
+        Some(version) => match Regex::new(version) {
+            Ok(regex) => regex.is_match(&context.os_version),
+            Err(e) => {
+                info!("Os version rule {version} isn't a valid regex: {e}");
+                false
+            }
+        },
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn context(os: &str, arch: &str, os_version: &str) -> LaunchContext {
+        LaunchContext {
+            os: os.to_string(),
+            arch: arch.to_string(),
+            os_version: os_version.to_string(),
+            features: HashMap::new(),
+        }
+    }
+
+    fn rules(value: Value) -> Vec<Rule> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn no_rules_allow_everything() {
+        assert!(is_allowed(None, &context("linux", "x86_64", "")));
+        assert!(is_allowed_value(Some(&Value::Null), &context("linux", "x86_64", "")));
+    }
+
+    #[test]
+    fn the_last_applying_rule_wins() {
+        let rules = rules(json!([{ "action": "allow" }, { "action": "disallow", "os": { "name": "osx" } }]));
+        assert!(is_allowed(Some(&rules), &context("linux", "x86_64", "")));
+        assert!(!is_allowed(Some(&rules), &context("osx", "arm64", "")));
+    }
+
+    #[test]
+    fn nothing_applying_disallows() {
+        let rules = rules(json!([{ "action": "allow", "os": { "name": "windows" } }]));
+        assert!(!is_allowed(Some(&rules), &context("linux", "x86_64", "")));
+    }
+
+    #[test]
+    fn os_arch_and_version_all_have_to_match() {
+        let rules = rules(json!([{
+            "action": "allow",
+            "os": { "name": "windows", "arch": "x86", "version": "^10\\." }
+        }]));
+        assert!(is_allowed(Some(&rules), &context("windows", "x86", "10.0.19045")));
+        assert!(!is_allowed(Some(&rules), &context("windows", "x86_64", "10.0.19045")));
+        assert!(!is_allowed(Some(&rules), &context("windows", "x86", "6.1.7601")));
+    }
+
+    #[test]
+    fn features_have_to_be_in_the_stated_state() {
+        let rules = rules(json!([{ "action": "allow", "features": { "has_custom_resolution": true } }]));
+        let context = context("linux", "x86_64", "");
+        assert!(!is_allowed(Some(&rules), &context));
+        assert!(is_allowed(Some(&rules), &context.with_feature("has_custom_resolution", true)));
+    }
+
+    #[test]
+    fn malformed_rules_allow_nothing() {
+        let rules = json!([{ "os": "linux" }]);
+        assert!(!is_allowed_value(Some(&rules), &context("linux", "x86_64", "")));
+    }
+}
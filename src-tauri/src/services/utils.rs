@@ -1,13 +1,13 @@
 use std::env;
-use crate::models::downloader::{LibraryRules, Rule, RuleOS};
 use crate::models::error::AppError;
 use crate::models::java::Java;
-use crate::models::platform::get_current_os;
+use crate::models::launch_context::LaunchContext;
 use crate::services::directory_manager::get_libraries_directory;
-use serde_json::{Map, Value};
+use crate::services::http_client::HttpClient;
+use serde_json::Value;
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use log::info;
 use sha1::{Digest, Sha1};
 use sha1::digest::FixedOutput;
@@ -62,10 +62,40 @@ pub fn verify_file_existence_with_sha<P: AsRef<Path>>(
     Ok(computed_hash.eq_ignore_ascii_case(expected_sha))
 }
 
-pub async fn load_json_url(url: &String) -> Option<Value> {
-    let result = reqwest::get(url).await.ok()?;
-    let text = result.text().await.ok()?;
-    serde_json::from_str(text.as_str()).ok()
+pub async fn load_json_url(client: &HttpClient, url: &String) -> Option<Value> {
+    client.get_json(url).await.ok()
+}
+
+/// Every file under `directory`, recursively. an unreadable directory counts as empty.
+pub fn list_files(directory: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(entries) = directory.read_dir() else {
+        return files;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(list_files(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Deletes the file or directory at `path`, one that is already gone counts as deleted.
+pub fn remove_if_exists(path: &Path) -> Result<(), AppError> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match result {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(AppError::FileDeleteFailed(format!("{}: {e}", path.display())))
+        }
+        _ => Ok(()),
+    }
 }
 
 pub fn vec_to_string(vec: Vec<String>, separator: String) -> String {
@@ -144,49 +174,6 @@ pub fn get_core_version(version_id: &String) -> Result<String, AppError> {
     Ok(format!("{}.{}", args[0], args[1]))
 }
 
-pub fn can_apply_rule(rule_obj: &Map<String, Value>) -> bool {
-    let rules = match rule_obj.get("rules").and_then(|r| r.as_array()) {
-        Some(r) => r,
-        None => return true,
-    };
-
-    let mut allowed = false;
-
-    for rule in rules {
-        if let Some(rule_map) = rule.as_object() {
-            let action = rule_map
-                .get("action")
-                .and_then(|a| a.as_str())
-                .unwrap_or("allow");
-            let applies = check_os_rule(rule_map);
-            if applies {
-                match action {
-                    "allow" => allowed = true,
-                    "disallow" => return false,
-                    _ => {}
-                }
-            }
-        }
-    }
-
-    allowed
-}
-
-pub fn check_os_rule(rule_map: &Map<String, Value>) -> bool {
-    let os_condition = match rule_map.get("os") {
-        Some(os) => os.as_object(),
-        None => return true,
-    };
-
-    let Some(os) = os_condition else { return true };
-
-    if let Some(name) = os.get("name").and_then(|n| n.as_str()) {
-        let current_os = get_current_os();
-        return current_os == name;
-    }
-    true
-}
-
 pub fn update_download_bar(progress: i64, app_handle: &AppHandle) {
     app_handle.emit("progressBar", progress)
         .unwrap_or_else(|x| info!("Failed to emit progress to progressBar event. detailed error: \n {x}"));
@@ -243,6 +230,24 @@ pub fn fetch_library_path(name: &String) -> Result<String, AppError> {
     Ok(format!("{group}/{artifact}/{version}/{artifact}-{version}.jar"))
 }
 
+pub const MAVEN_CENTRAL: &str = "https://repo1.maven.org/maven2/";
+
+/// Mojang ships no arm64 Linux natives of LWJGL, the build of the same version from Maven Central stands in for
+/// the x86_64 one there. returns its maven path, e.g. `org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-linux-arm64.jar`.
+///
+/// LWJGL 2 (`org.lwjgl.lwjgl`) was never built for arm64, its natives have nothing to stand in for them.
+pub fn arm64_natives_path(library_name: &str, classifier: &str, context: &LaunchContext) -> Option<String> {
+    if context.os != "linux" || context.arch != "arm64" || classifier != "natives-linux" {
+        return None;
+    }
+    let mut args = library_name.split(':');
+    let (group, artifact, version) = (args.next()?, args.next()?, args.next()?);
+    if group != "org.lwjgl" {
+        return None;
+    }
+    Some(format!("org/lwjgl/{artifact}/{version}/{artifact}-{version}-natives-linux-arm64.jar"))
+}
+
 pub fn fetch_unofficial_library_repos(path: &String) -> Vec<String> {
     vec![
         format!("https://maven.minecraftforge.net/{path}"),
@@ -250,47 +255,6 @@ pub fn fetch_unofficial_library_repos(path: &String) -> Vec<String> {
     ]
 }
 
-fn push_rule(_rules: &[Rule], pushing_vec: &mut Vec<String>, rule_os: &Option<RuleOS>) {
-    if let Some(os) = rule_os {
-        if let Some(name) = &os.name {
-            pushing_vec.push(name.to_string());
-        }
-    } else {
-        pushing_vec.push("osx".to_string());
-        pushing_vec.push("windows".to_string());
-        pushing_vec.push("linux".to_string());
-    }
-}
-
-pub fn fetch_rules(value: Option<&Vec<Rule>>) -> LibraryRules {
-    if let Some(rules) = value {
-        let mut allowed = vec![];
-        let mut disallowed = vec![];
-        for rule in rules {
-            let rule_action = &rule.action;
-            let rule_os = &rule.os;
-            if rule_action == "allow" {
-                push_rule(rules, &mut allowed, rule_os);
-            } else if rule_action == "disallow" {
-                push_rule(rules, &mut disallowed, rule_os);
-            }
-        }
-        LibraryRules {
-            allowed_oses: allowed,
-            disallowed_oses: disallowed,
-        }
-    } else {
-        LibraryRules {
-            allowed_oses: vec![
-                "osx".to_string(),
-                "windows".to_string(),
-                "linux".to_string(),
-            ],
-            disallowed_oses: vec![],
-        }
-    }
-}
-
 pub fn is_legacy(version: &String) -> bool {
     if !version.starts_with("1.") {
         return false;
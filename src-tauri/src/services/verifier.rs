@@ -0,0 +1,111 @@
+use crate::models::error::AppError;
+use crate::models::install_plan::{InstallPlan, PlannedFileKind};
+use crate::models::verification::VerificationReport;
+use crate::models::versions::MinecraftVersion;
+use crate::services::directory_manager::get_java_dir;
+use crate::services::install_planner::{plan_offline_launch, unreadable_version_json};
+use crate::services::object_store::archive_entries;
+use crate::services::utils::list_files;
+use log::info;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Checks every file the version launches with (the version it inherits from included) against the SHA1 and size
+/// its manifests expect. it only reads what's on the disk, nothing is fetched, written or repaired.
+/// a version JSON that can't be read is reported on its own, as its files can't be told without it.
+pub async fn verify_version(version: &MinecraftVersion) -> Result<VerificationReport, AppError> {
+    let mut report = VerificationReport::new(version.id.clone());
+    let mut plan = match plan_offline_launch(version).await {
+        Ok(plan) => plan,
+        Err(e) => {
+            let file = unreadable_version_json(version).ok_or(e)?;
+            info!("{} can't be verified, {} is unreadable", version.id, file.path.display());
+            report.checked = 1;
+            if file.path.exists() {
+                report.corrupt.push(file);
+            } else {
+                report.missing.push(file);
+            }
+            return Ok(report);
+        }
+    };
+    plan.check_installed();
+
+    for file in &plan.files {
+        report.checked += 1;
+        if file.installed {
+            continue;
+        }
+        if file.path.exists() {
+            report.corrupt.push(file.clone());
+        } else {
+            report.missing.push(file.clone());
+        }
+    }
+    report.extra.extend(find_extra_runtime_files(&plan));
+    report.extra.extend(find_extra_natives(&plan));
+
+    info!(
+        "Verified {} files of {}: {} missing, {} corrupt, {} extra",
+        report.checked,
+        report.version,
+        report.missing.len(),
+        report.corrupt.len(),
+        report.extra.len()
+    );
+    Ok(report)
+}
+
+/// Files in the Java runtimes the manifest doesn't list. runtimes whose manifest was never cached are skipped,
+/// there's nothing to tell their files apart with.
+fn find_extra_runtime_files(plan: &InstallPlan) -> Vec<PathBuf> {
+    let java_dir = get_java_dir();
+    let mut runtimes: HashMap<PathBuf, HashSet<PathBuf>> = HashMap::new();
+    for file in plan.files.iter().filter(|x| x.kind == PlannedFileKind::JavaRuntime && !x.sha1.is_empty()) {
+        if let Some(component) = file.path.strip_prefix(&java_dir).ok().and_then(|x| x.iter().next()) {
+            runtimes.entry(java_dir.join(component)).or_default().insert(file.path.clone());
+        }
+    }
+
+    let mut extra = Vec::new();
+    for (runtime_dir, expected) in runtimes {
+        let release = runtime_dir.join("release");
+        for path in list_files(&runtime_dir) {
+            // The release file is written by the launcher itself, links aren't downloaded but made by the system.
+            let is_link = path.symlink_metadata().map(|x| x.file_type().is_symlink()).unwrap_or(false);
+            if path != release && !is_link && !expected.contains(&path) {
+                extra.push(path);
+            }
+        }
+    }
+    extra
+}
+
+/// Files in the natives directory that none of the natives jars contains. skipped when a jar can't be read, it's
+/// already reported as missing or corrupt.
+fn find_extra_natives(plan: &InstallPlan) -> Vec<PathBuf> {
+    let natives = plan.files.iter().filter(|x| x.kind == PlannedFileKind::Native);
+    let (directories, jars): (Vec<_>, Vec<_>) = natives.partition(|x| x.url.is_empty());
+    let Some(directory) = directories.first() else {
+        return Vec::new();
+    };
+
+    let mut expected = HashSet::new();
+    for jar in jars {
+        match archive_entries(&jar.path, &jar.exclude) {
+            Ok(entries) => expected.extend(entries),
+            Err(e) => {
+                info!("Can't tell the extra natives apart, {} couldn't be read: {e}", jar.path.display());
+                return Vec::new();
+            }
+        }
+    }
+    list_files(&directory.path)
+        .into_iter()
+        .filter(|x| {
+            x.strip_prefix(&directory.path)
+                .map(|x| !expected.contains(x))
+                .unwrap_or(false)
+        })
+        .collect()
+}
@@ -1,33 +1,38 @@
+use crate::models::config::DownloadSettings;
 use crate::models::downloader::Manifest;
 use crate::models::error::AppError::FileReadFailed;
 use crate::models::error::{AppError, Void};
-use crate::models::mirror::Mirror;
 use crate::models::versions::MinecraftVersion;
-use crate::services::directory_manager::{get_versions_directory, version_manifest_directory};
-use crate::services::game_downloader::download_file;
+use crate::services::directory_manager::get_versions_directory;
+use crate::services::http_client::HttpClient;
+use crate::services::metadata_cache::{get_cached_entry, get_cached_json};
 use crate::GLOBAL_CACHE;
 use log::debug;
 
-/// Loads the version manifest, will download the file version manifest through the given mirror, if it doesn't exist
-pub async fn load_version_manifest(mirror: &Mirror) -> Result<Manifest, AppError> {
-    if !version_manifest_directory().exists() {
-        download_version_manifest(mirror).await?;
-    }
-    load_version_manifest_local()
+const VERSION_MANIFEST_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+
+/// Loads the version manifest through the metadata cache, it's only fetched when the cached copy is missing or
+/// older than the configured TTL.
+pub async fn load_version_manifest(client: &HttpClient, settings: &DownloadSettings) -> Result<Manifest, AppError> {
+    get_cached_json(client, VERSION_MANIFEST_URL, settings).await
 }
 
-/// Downloads the latest version manifest available through the given mirror whether it already exists or not.
+/// Revalidates the version manifest through the given mirrors whether the cached copy is still fresh or not.
 /// returns Manifest itself if everything goes well or else an error will be dropped.
-pub async fn refresh_version_manifest(mirror: &Mirror) -> Result<Manifest, AppError> {
-    download_version_manifest(mirror).await?;
-    load_version_manifest_local()
+pub async fn refresh_version_manifest(client: &HttpClient, settings: &DownloadSettings) -> Result<Manifest, AppError> {
+    let settings = DownloadSettings {
+        metadata_cache_ttl: 0,
+        ..settings.clone()
+    };
+    get_cached_json(client, VERSION_MANIFEST_URL, &settings).await
 }
 
-/// Loads version manifest only from local files, will throw an error if it doesn't exist, its best to use load_version_manifest.
+/// Loads the cached version manifest without touching the network, will throw an error if it was never fetched,
+/// its best to use load_version_manifest.
 pub fn load_version_manifest_local() -> Result<Manifest, AppError> {
-    let path = version_manifest_directory();
-    let text = std::fs::read_to_string(&path).map_err(|x| FileReadFailed(x.to_string()))?;
-    serde_json::from_str(text.as_str()).map_err(|x| AppError::JsonParseFailed(x.to_string()))
+    let entry = get_cached_entry(VERSION_MANIFEST_URL)
+        .ok_or_else(|| FileReadFailed("The version manifest was never fetched".to_string()))?;
+    serde_json::from_str(entry.body.as_str()).map_err(|x| AppError::JsonParseFailed(x.to_string()))
 }
 
 pub async fn load_installed_versions() {
@@ -62,13 +67,3 @@ pub async fn load_installed_versions() {
     let mut global = GLOBAL_CACHE.lock().await;
     global.versions = versions;
 }
-
-pub async fn download_version_manifest(mirror: &Mirror) -> Result<(), AppError> {
-    let url = mirror
-        .parse_url(&"https://launchermeta.mojang.com/mc/game/version_manifest.json".to_string());
-    download_file(
-        url.to_string(),
-        &version_manifest_directory().to_string_lossy().into_owned(),
-    )
-        .await
-}
\ No newline at end of file
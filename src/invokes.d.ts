@@ -24,6 +24,25 @@ export type Invokes = WithDefaultError<{
     args: undefined;
     returns: VersionCategory[];
   };
+  get_neoforge_versions: {
+    args: undefined;
+    returns: VersionCategory[];
+  };
+  get_quilt_versions: {
+    args: undefined;
+    returns: VersionCategory[];
+  };
+  get_liteloader_versions: {
+    args: undefined;
+    returns: VersionCategory[];
+  };
+  add_optifine: {
+    args: {
+      installer?: string;
+      versionId: string;
+    };
+    returns: string | null;
+  };
   get_vanilla_versions: {
     args: undefined;
     returns: VersionCategory[];
@@ -115,8 +134,53 @@ export type Invokes = WithDefaultError<{
       versionLoader: VersionLoader;
       name: string;
     };
+    returns: string;
+  };
+  plan_install: {
+    args: {
+      versionLoader: VersionLoader;
+    };
+    returns: InstallPlan;
+  };
+  list_downloads: {
+    args: undefined;
+    returns: DownloadProgress[];
+  };
+  pause_download: {
+    args: {
+      taskId: string;
+    };
+    returns: void;
+  };
+  resume_download: {
+    args: {
+      taskId: string;
+    };
     returns: void;
   };
+  cancel_download: {
+    args: {
+      taskId: string;
+    };
+    returns: void;
+  };
+  set_download_bandwidth_limit: {
+    args: {
+      taskId: string;
+      limit: number;
+    };
+    returns: void;
+  };
+  clear_finished_downloads: {
+    args: undefined;
+    returns: void;
+  };
+  collect_garbage: {
+    args: {
+      confirm: boolean;
+    };
+    returns: GarbageReport;
+  };
   reload_version_manifest: {
     args: undefined;
     returns: void;
@@ -149,9 +213,22 @@ export type Invokes = WithDefaultError<{
       selectedVersion: string;
       repairMode: boolean;
       profile: string;
+      quickPlay?: QuickPlay;
     };
     returns: void;
   };
+  get_offline_launch_blockers: {
+    args: {
+      selectedVersion: string;
+    };
+    returns: PlannedFile[];
+  };
+  verify_version: {
+    args: {
+      selectedVersion: string;
+    };
+    returns: VerificationReport;
+  };
   get_minimum_ram_usage: {
     args: undefined;
     returns: number;
@@ -182,6 +259,16 @@ export type Invokes = WithDefaultError<{
     };
     returns: void;
   };
+  is_offline_mode: {
+    args: undefined;
+    returns: boolean;
+  };
+  set_offline_mode: {
+    args: {
+      toggle: boolean;
+    };
+    returns: void;
+  };
   should_use_dedicated_gpu: {
     args: undefined;
     returns: boolean;
@@ -192,6 +279,71 @@ export type Invokes = WithDefaultError<{
     };
     returns: void;
   };
+  set_resolution: {
+    args: {
+      width: number;
+      height: number;
+    };
+    returns: void;
+  };
+  get_resolution: {
+    args: undefined;
+    returns: [number, number];
+  };
+  set_demo_mode: {
+    args: {
+      toggle: boolean;
+    };
+    returns: void;
+  };
+  is_demo_mode: {
+    args: undefined;
+    returns: boolean;
+  };
+  get_max_concurrent_downloads: {
+    args: undefined;
+    returns: number;
+  };
+  set_max_concurrent_downloads: {
+    args: {
+      limit: number;
+    };
+    returns: void;
+  };
+  get_bandwidth_limit: {
+    args: undefined;
+    returns: number;
+  };
+  set_bandwidth_limit: {
+    args: {
+      limit: number;
+    };
+    returns: void;
+  };
+  get_job_bandwidth_limit: {
+    args: undefined;
+    returns: number;
+  };
+  set_job_bandwidth_limit: {
+    args: {
+      limit: number;
+    };
+    returns: void;
+  };
+  get_metadata_cache_ttl: {
+    args: undefined;
+    returns: number;
+  };
+  set_metadata_cache_ttl: {
+    args: {
+      seconds: number;
+    };
+    returns: void;
+  };
+  clear_metadata_cache: {
+    args: undefined;
+    returns: void;
+  };
   get_available_mirrors: {
     args: undefined;
     returns: Mirror[];
@@ -206,6 +358,50 @@ export type Invokes = WithDefaultError<{
     };
     returns: void;
   };
+  benchmark_mirrors: {
+    args: undefined;
+    returns: MirrorBenchmark[];
+  };
+  get_mirror_benchmarks: {
+    args: undefined;
+    returns: MirrorBenchmark[];
+  };
+  set_auto_mirror: {
+    args: {
+      toggle: boolean;
+    };
+    returns: void;
+  };
+  is_auto_mirror: {
+    args: undefined;
+    returns: boolean;
+  };
+  get_proxy_settings: {
+    args: undefined;
+    returns: ProxySettings;
+  };
+  set_proxy_settings: {
+    args: {
+      proxy: ProxySettings;
+    };
+    returns: void;
+  };
+  test_proxy: {
+    args: {
+      proxy: ProxySettings;
+    };
+    returns: number;
+  };
+  get_mirrors: {
+    args: undefined;
+    returns: Mirror[];
+  };
+  set_mirrors: {
+    args: {
+      mirrors: Mirror[];
+    };
+    returns: void;
+  };
   import_mirror: {
     args: {
       json: string;
@@ -261,10 +457,47 @@ export interface Mirror {
   description: string;
   name: string;
   url: string;
+  hosts?: MirrorHosts;
+  rewrites?: PathRewrite[];
+}
+
+export interface MirrorHosts {
+  fabricMeta: string | null;
+  fabricMaven: string | null;
+  forgeMaven: string | null;
+  forgeFiles: string | null;
+  neoforgeMaven: string | null;
+  quiltMeta: string | null;
+  quiltMaven: string | null;
+  modrinthApi: string | null;
+  modrinthCdn: string | null;
+}
+
+export interface PathRewrite {
+  from: string;
+  to: string;
+}
+
+export interface ProxySettings {
+  kind: "SYSTEM" | "NONE" | "HTTP" | "HTTPS" | "SOCKS5";
+  host: string;
+  port: number;
+  username: string;
+  password: string;
+  bypass: string;
+}
+
+export interface MirrorBenchmark {
+  name: string;
+  latencyMs: number | null;
+  throughput: number | null;
+  healthy: boolean;
+  error: string | null;
+  testedAt: number;
 }
 
 export interface MinecraftVersion {
-  base: "FABRIC" | "FORGE" | "NEO_FORGE" | "LITE_LOADER" | "VANILLA";
+  base: "FABRIC" | "FORGE" | "NEOFORGE" | "LITELOADER" | "QUILT" | "VANILLA";
   date: string;
   id: string;
   inheritedVersion?: string;
@@ -277,6 +510,7 @@ export enum VersionBase {
   NEOFORGE = 2,
   FABRIC = 3,
   LITELOADER = 4,
+  QUILT = 5,
 }
 
 export interface Profile {
@@ -291,6 +525,93 @@ export interface VersionLoader {
   id: string;
 }
 
+export type PlannedFileKind =
+  | "versionJson"
+  | "installer"
+  | "javaRuntime"
+  | "library"
+  | "native"
+  | "client"
+  | "logging"
+  | "assetIndex"
+  | "asset";
+
+export interface PlannedFile {
+  kind: PlannedFileKind;
+  url: string;
+  path: string;
+  sha1: string;
+  size: number;
+  installed: boolean;
+  exclude: string[];
+}
+
+export interface VerificationReport {
+  version: string;
+  checked: number;
+  missing: PlannedFile[];
+  corrupt: PlannedFile[];
+  extra: string[];
+}
+
+export type GarbageKind =
+  | "library"
+  | "assetIndex"
+  | "asset"
+  | "javaRuntime"
+  | "storedObject";
+
+export interface GarbageFile {
+  kind: GarbageKind;
+  path: string;
+  size: number;
+}
+
+export interface GarbageReport {
+  files: GarbageFile[];
+  reclaimableBytes: number;
+  deleted: boolean;
+}
+
+export interface PlannedRuntime {
+  component: string;
+  version: string;
+}
+
+export interface InstallPlan {
+  version: string;
+  files: PlannedFile[];
+  runtimes: PlannedRuntime[];
+  totalBytes: number;
+  downloadBytes: number;
+  notes: string[];
+}
+
+export type DownloadJobStatus =
+  | "queued"
+  | "running"
+  | "paused"
+  | "completed"
+  | "failed"
+  | "cancelled";
+
+export interface DownloadProgress {
+  taskId: string;
+  version: string;
+  name: string;
+  status: DownloadJobStatus;
+  stage: string;
+  currentFile: string;
+  bytesDone: number;
+  bytesTotal: number;
+  filesDone: number;
+  filesTotal: number;
+  speed: number;
+  eta: number | null;
+  bandwidthLimit: number;
+  error: string | null;
+}
+
 export interface VersionCategory {
   name: string;
   versions: VersionLoader[];
@@ -304,17 +625,31 @@ export interface Config {
 
 export interface DownloadSettings {
   mirror: string;
+  max_concurrent_downloads: number;
+  auto_mirror: "TRUE" | "FALSE";
+  metadata_cache_ttl: number;
+  bandwidth_limit: number;
+  job_bandwidth_limit: number;
 }
 
 export interface LauncherSettings {
   language: string;
+  offline_mode: "TRUE" | "FALSE";
 }
 
 export interface LaunchOptions {
   ramusage: number;
   username: string;
+  resolution_width: number;
+  resolution_height: number;
+  demo_mode: "TRUE" | "FALSE";
 }
 
+export type QuickPlay =
+  | { type: "singleplayer"; target: string }
+  | { type: "multiplayer"; target: string }
+  | { type: "realms"; target: string };
+
 export interface ModInfo {
   description: string;
   enabled: boolean;